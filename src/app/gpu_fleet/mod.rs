@@ -0,0 +1,5 @@
+pub mod update;
+pub mod view;
+
+pub use update::handle_key;
+pub use view::render;
@@ -0,0 +1,61 @@
+use crate::app::{App, AppMode};
+use crossterm::event::KeyCode;
+
+/// Minimum free GPU memory filter steps, in GB (0 disables the filter).
+const FREE_MEMORY_STEPS_GB: [u32; 5] = [0, 8, 24, 40, 80];
+
+/// Minimum idle duration filter steps, in minutes (0 disables the filter).
+const IDLE_STEPS_MINUTES: [u64; 4] = [0, 10, 30, 60];
+
+pub fn handle_key(app: &mut App, key: crossterm::event::KeyEvent) {
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => {
+            if app.fleet_rows.is_empty() {
+                return;
+            }
+            let new_index = match app.fleet_table_state.selected() {
+                Some(i) if i + 1 < app.fleet_rows.len() => i + 1,
+                _ => 0,
+            };
+            app.fleet_table_state.select(Some(new_index));
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            if app.fleet_rows.is_empty() {
+                return;
+            }
+            let new_index = match app.fleet_table_state.selected() {
+                Some(0) | None => app.fleet_rows.len().saturating_sub(1),
+                Some(i) => i - 1,
+            };
+            app.fleet_table_state.select(Some(new_index));
+        }
+        KeyCode::Char('f') => {
+            app.fleet_min_free_gb = next_step(&FREE_MEMORY_STEPS_GB, app.fleet_min_free_gb);
+            app.fleet_table_state.select(Some(0));
+        }
+        KeyCode::Char('i') => {
+            app.fleet_idle_minutes = next_step(&IDLE_STEPS_MINUTES, app.fleet_idle_minutes);
+            app.fleet_table_state.select(Some(0));
+        }
+        KeyCode::Enter => {
+            let selected = app
+                .fleet_table_state
+                .selected()
+                .and_then(|i| app.fleet_rows.get(i));
+            if let Some((host_id, _)) = selected {
+                app.selected_id = Some(host_id.clone());
+                app.detail_return_mode = AppMode::GpuFleet;
+                app.mode = AppMode::Detail;
+            }
+        }
+        KeyCode::Char('q') | KeyCode::Esc => {
+            app.mode = AppMode::List;
+        }
+        _ => {}
+    }
+}
+
+fn next_step<T: Copy + PartialEq>(steps: &[T], current: T) -> T {
+    let position = steps.iter().position(|s| *s == current).unwrap_or(0);
+    steps[(position + 1) % steps.len()]
+}
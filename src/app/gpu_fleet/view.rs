@@ -0,0 +1,200 @@
+use crate::app::App;
+use crate::app::formatting::format_uptime;
+use crate::app::ssh_list::table_theme::TableColors;
+use crate::app::states::{GpuDevice, GpuInfo};
+use ratatui::prelude::*;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::*;
+use std::time::Instant;
+
+pub fn render(app: &mut App, frame: &mut Frame) {
+    let area = frame.area();
+    let colors = TableColors::default();
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .split(area);
+
+    let title = Paragraph::new("Free GPU Finder")
+        .style(Style::default().add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center);
+    frame.render_widget(title, chunks[0]);
+
+    let hosts_guard = futures::executor::block_on(app.ssh_hosts.lock());
+    let gpu_guard = futures::executor::block_on(app.gpu_info.lock());
+
+    let now = Instant::now();
+    let min_free_mb = app.fleet_min_free_gb * 1024;
+    let min_idle_secs = app.fleet_idle_minutes * 60;
+
+    let mut entries: Vec<(&str, &str, &GpuDevice)> = gpu_guard
+        .iter()
+        .filter_map(|(id, gpu)| match gpu {
+            GpuInfo::Success { devices } => {
+                let host = hosts_guard.get(id)?;
+                Some(
+                    devices
+                        .iter()
+                        .map(move |d| (id.as_str(), host.name.as_str(), d)),
+                )
+            }
+            _ => None,
+        })
+        .flatten()
+        .filter(|(_, _, d)| d.memory_free_mb() >= min_free_mb)
+        .filter(|(_, _, d)| {
+            min_idle_secs == 0
+                || d.idle_since
+                    .is_some_and(|since| now.duration_since(since).as_secs() >= min_idle_secs)
+        })
+        .collect();
+
+    // Most free memory first; among equals, the least busy GPU wins
+    entries.sort_by(|(_, a_name, a), (_, b_name, b)| {
+        b.memory_free_mb()
+            .cmp(&a.memory_free_mb())
+            .then(a.utilization_percent.cmp(&b.utilization_percent))
+            .then(a_name.cmp(b_name))
+            .then(a.index.cmp(&b.index))
+    });
+
+    let total_gpus: usize = gpu_guard
+        .values()
+        .map(|g| match g {
+            GpuInfo::Success { devices } => devices.len(),
+            _ => 0,
+        })
+        .sum();
+    let idle_gpus = entries.iter().filter(|(_, _, d)| d.is_idle()).count();
+
+    let free_filter = match app.fleet_min_free_gb {
+        0 => "any".to_string(),
+        gb => format!("≥ {gb} GB"),
+    };
+    let idle_filter = match app.fleet_idle_minutes {
+        0 => "any".to_string(),
+        minutes => format!("≥ {minutes} min"),
+    };
+    let filter_line = Line::from(vec![
+        Span::raw(format!("Showing {}/{} GPUs  ", entries.len(), total_gpus)),
+        Span::styled("● ", Style::default().fg(Color::Green)),
+        Span::raw(format!("Idle: {idle_gpus}  ")),
+        Span::styled("Free mem: ", Style::default().fg(Color::Cyan)),
+        Span::raw(format!("{free_filter}  ")),
+        Span::styled("Idle for: ", Style::default().fg(Color::Cyan)),
+        Span::raw(idle_filter),
+    ]);
+    let filters = Paragraph::new(vec![filter_line]).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Filters")
+            .border_style(Style::default().fg(Color::White)),
+    );
+    frame.render_widget(filters, chunks[1]);
+
+    app.fleet_rows = entries
+        .iter()
+        .map(|(id, _, d)| (id.to_string(), d.index))
+        .collect();
+    if app.fleet_rows.is_empty() {
+        app.fleet_table_state.select(None);
+    } else if app
+        .fleet_table_state
+        .selected()
+        .is_none_or(|i| i >= app.fleet_rows.len())
+    {
+        app.fleet_table_state.select(Some(0));
+    }
+
+    let rows = entries.iter().map(|(_, host_name, d)| {
+        let idle_cell = match d.idle_since {
+            Some(since) => Cell::from(Span::styled(
+                format_uptime(now.duration_since(since).as_secs()),
+                Style::default().fg(Color::Green),
+            )),
+            None => Cell::from("busy"),
+        };
+        let mut owners: Vec<&str> = d.processes.iter().map(|p| p.user.as_str()).collect();
+        owners.sort_unstable();
+        owners.dedup();
+
+        Row::new(vec![
+            Cell::from(host_name.to_string()),
+            Cell::from(d.index.to_string()),
+            Cell::from(d.name.clone()),
+            Cell::from(format!(
+                "{:.1}/{:.1} GB",
+                d.memory_free_mb() as f32 / 1024.0,
+                d.memory_total_mb as f32 / 1024.0
+            )),
+            Cell::from(format!("{}%", d.utilization_percent)),
+            Cell::from(format!("{}C", d.temperature_c)),
+            idle_cell,
+            Cell::from(owners.join(",")),
+        ])
+        .style(Style::default().bg(colors.normal_row_color))
+    });
+
+    let header = Row::new(vec![
+        Cell::from("Host"),
+        Cell::from("GPU"),
+        Cell::from("Name"),
+        Cell::from("Free"),
+        Cell::from("Util"),
+        Cell::from("Temp"),
+        Cell::from("Idle"),
+        Cell::from("Owners"),
+    ])
+    .style(
+        Style::default()
+            .fg(colors.header_fg)
+            .bg(colors.header_bg)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(16),
+            Constraint::Length(4),
+            Constraint::Length(28),
+            Constraint::Length(16),
+            Constraint::Length(6),
+            Constraint::Length(6),
+            Constraint::Length(8),
+            Constraint::Min(10),
+        ],
+    )
+    .header(header)
+    .block(Block::default().borders(Borders::ALL).title("GPUs"))
+    .row_highlight_style(colors.selected_row_style)
+    .highlight_symbol("▶ ")
+    .highlight_spacing(HighlightSpacing::Always);
+
+    frame.render_stateful_widget(table, chunks[2], &mut app.fleet_table_state);
+
+    let footer = Paragraph::new(vec![Line::from(
+        "ESC: Back | ↑↓: Scroll | Enter: Host details | f: Free mem filter | i: Idle filter",
+    )])
+    .alignment(Alignment::Center)
+    .style(
+        Style::default()
+            .fg(colors.row_fg)
+            .bg(colors.normal_row_color),
+    )
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Controls")
+            .border_style(Style::default().fg(colors.footer_border_color)),
+    );
+
+    frame.render_widget(footer, chunks[3]);
+}
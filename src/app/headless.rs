@@ -0,0 +1,219 @@
+use super::App;
+use super::states::{Metric, SshStatus, history_store::history_path, unix_now};
+use color_eyre::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task;
+
+/// How often the latest values of every host are logged and the snapshot
+/// file is rewritten.
+const REPORT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Latest state of every host, rewritten each report so scripts and other
+/// machines (over ssh, NFS, a static file server, ...) can read it.
+pub fn snapshot_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("ssh-monitoring/snapshot.json"))
+}
+
+#[derive(Debug, Serialize)]
+struct Snapshot {
+    generated_at: u64,
+    hosts: Vec<HostSnapshot>,
+}
+
+#[derive(Debug, Serialize)]
+struct HostSnapshot {
+    id: String,
+    name: String,
+    /// `connected`, `failed` or `loading`.
+    status: &'static str,
+    error: Option<String>,
+    last_seen: Option<u64>,
+    cpu_percent: Option<f64>,
+    memory_percent: Option<f64>,
+    gpu_percent: Option<f64>,
+}
+
+impl App {
+    /// Runs the collectors without a terminal until SIGTERM or Ctrl-C,
+    /// logging host state as structured events and writing it to the
+    /// snapshot file. Samples keep going to the history file.
+    pub async fn run_headless(self) -> Result<()> {
+        let host_count = self.ssh_hosts.lock().await.len();
+        let history_file = history_path().filter(|_| self.config.history.persist);
+        let snapshot_file = snapshot_path();
+        tracing::info!(
+            hosts = host_count,
+            history_file = history_file.as_ref().map(|p| p.display().to_string()),
+            snapshot_file = snapshot_file.as_ref().map(|p| p.display().to_string()),
+            "headless mode started"
+        );
+        if history_file.is_none() {
+            tracing::warn!("history persistence is disabled");
+        }
+
+        self.start_tasks();
+
+        let shutdown = shutdown_signal();
+        tokio::pin!(shutdown);
+        let mut report = tokio::time::interval(REPORT_INTERVAL);
+        let mut last_statuses: HashMap<String, SshStatus> = HashMap::new();
+        loop {
+            tokio::select! {
+                _ = report.tick() => {
+                    let snapshot = self.report_host_states(&mut last_statuses).await;
+                    if let Some(path) = snapshot_file.clone() {
+                        match task::spawn_blocking(move || write_snapshot(&path, &snapshot)).await {
+                            Ok(Ok(())) => {}
+                            Ok(Err(e)) => tracing::warn!(error = %e, "failed to write snapshot"),
+                            Err(e) => tracing::warn!(error = %e, "snapshot thread error"),
+                        }
+                    }
+                }
+                signal = &mut shutdown => {
+                    tracing::info!(signal = signal?, "shutting down");
+                    break;
+                }
+            }
+        }
+
+        // Leave a compacted file behind for the next start
+        let store = Arc::clone(&self.history_store);
+        match task::spawn_blocking(move || store.compact()).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => tracing::warn!(error = %e, "failed to compact history"),
+            Err(e) => tracing::warn!(error = %e, "history compaction thread error"),
+        }
+        tracing::info!("headless mode stopped");
+        Ok(())
+    }
+
+    /// Logs connection changes, then one event per host with its latest
+    /// samples, and returns the same state as a snapshot.
+    async fn report_host_states(&self, last_statuses: &mut HashMap<String, SshStatus>) -> Snapshot {
+        let hosts = self.ssh_hosts.lock().await;
+        let statuses = self.ssh_statuses.lock().await;
+        let history = self.history.lock().await;
+
+        let mut ids: Vec<&String> = hosts.keys().collect();
+        ids.sort();
+        let mut snapshot = Snapshot {
+            generated_at: unix_now(),
+            hosts: Vec::with_capacity(ids.len()),
+        };
+        for id in ids {
+            let status = statuses.get(id).cloned().unwrap_or(SshStatus::Loading);
+            if last_statuses.get(id) != Some(&status) {
+                match &status {
+                    SshStatus::Connected => tracing::info!(host = %id, "host connected"),
+                    SshStatus::Failed(e) => {
+                        tracing::warn!(host = %id, error = %e, "host unreachable")
+                    }
+                    SshStatus::Loading => {}
+                }
+                last_statuses.insert(id.clone(), status.clone());
+            }
+
+            let host_history = history.get(id);
+            let latest =
+                |metric: Metric| host_history.and_then(|h| h.get(metric).last().map(|(_, v)| v));
+            let host = HostSnapshot {
+                id: id.clone(),
+                name: hosts[id].name.clone(),
+                status: match status {
+                    SshStatus::Connected => "connected",
+                    SshStatus::Failed(_) => "failed",
+                    SshStatus::Loading => "loading",
+                },
+                error: match status {
+                    SshStatus::Failed(e) => Some(e),
+                    _ => None,
+                },
+                last_seen: host_history.and_then(|h| h.last_seen()),
+                cpu_percent: latest(Metric::Cpu),
+                memory_percent: latest(Metric::Memory),
+                gpu_percent: latest(Metric::Gpu),
+            };
+            if host_history.is_some() {
+                tracing::info!(
+                    host = %id,
+                    cpu_percent = host.cpu_percent,
+                    memory_percent = host.memory_percent,
+                    gpu_percent = host.gpu_percent,
+                    last_seen = host.last_seen,
+                    "host sample"
+                );
+            }
+            snapshot.hosts.push(host);
+        }
+        snapshot
+    }
+}
+
+/// Writes the snapshot aside and renames it over `path`, so readers never
+/// see a half-written file.
+fn write_snapshot(path: &Path, snapshot: &Snapshot) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(snapshot)?)?;
+    fs::rename(&tmp, path)
+}
+
+/// Resolves with the name of the signal that asked the process to stop.
+async fn shutdown_signal() -> Result<&'static str> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        let mut terminate = signal(SignalKind::terminate())?;
+        tokio::select! {
+            _ = terminate.recv() => Ok("SIGTERM"),
+            result = tokio::signal::ctrl_c() => result.map(|_| "SIGINT").map_err(Into::into),
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c().await?;
+        Ok("SIGINT")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_snapshot_replaces_file() {
+        let dir = std::env::temp_dir().join(format!("ssh-monitoring-test-{}", std::process::id()));
+        let path = dir.join("snapshot.json");
+        let snapshot = |cpu| Snapshot {
+            generated_at: 1_800_000_000,
+            hosts: vec![HostSnapshot {
+                id: "gpu1".to_string(),
+                name: "gpu1".to_string(),
+                status: "connected",
+                error: None,
+                last_seen: Some(1_799_999_990),
+                cpu_percent: Some(cpu),
+                memory_percent: None,
+                gpu_percent: None,
+            }],
+        };
+
+        write_snapshot(&path, &snapshot(10.0)).unwrap();
+        write_snapshot(&path, &snapshot(42.5)).unwrap();
+        let written: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(written["hosts"][0]["status"], "connected");
+        assert_eq!(written["hosts"][0]["cpu_percent"], 42.5);
+        assert!(written["hosts"][0]["memory_percent"].is_null());
+    }
+}
@@ -0,0 +1,116 @@
+use crate::app::App;
+use crate::app::states::{
+    AuthLogInfo, ClockInfo, ContainerInfo, CpuInfo, DiskInfo, DiskIoInfo, GpuInfo, LoadInfo,
+    MemoryInfo, NetworkInfo, OsInfo, PortInfo, ProcessInfo, SensorInfo, ServiceInfo, SessionInfo,
+    SshStatus, UpdateInfo,
+};
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+
+/// What every collector last reported for one host, copied out of the shared
+/// maps so views render without holding a lock per collector.
+#[derive(Debug, Clone, Default)]
+pub struct HostSnapshot {
+    pub status: Option<SshStatus>,
+    pub cpu: Option<CpuInfo>,
+    pub disk: Option<DiskInfo>,
+    pub disk_io: Option<DiskIoInfo>,
+    pub memory: Option<MemoryInfo>,
+    pub network: Option<NetworkInfo>,
+    pub os: Option<OsInfo>,
+    pub gpu: Option<GpuInfo>,
+    pub load: Option<LoadInfo>,
+    pub processes: Option<ProcessInfo>,
+    pub sensors: Option<SensorInfo>,
+    pub services: Option<ServiceInfo>,
+    pub containers: Option<ContainerInfo>,
+    pub sessions: Option<SessionInfo>,
+    pub auth_log: Option<AuthLogInfo>,
+    pub updates: Option<UpdateInfo>,
+    pub clock: Option<ClockInfo>,
+    pub ports: Option<PortInfo>,
+}
+
+impl HostSnapshot {
+    /// Snapshots of the given hosts, taking each collector's lock once.
+    pub fn collect<'a>(
+        app: &App,
+        ids: impl IntoIterator<Item = &'a String>,
+    ) -> HashMap<String, HostSnapshot> {
+        let mut snapshots: HashMap<String, HostSnapshot> = ids
+            .into_iter()
+            .map(|id| (id.clone(), HostSnapshot::default()))
+            .collect();
+
+        copy_into(&app.ssh_statuses, &mut snapshots, |s| &mut s.status);
+        copy_into(&app.cpu_info, &mut snapshots, |s| &mut s.cpu);
+        copy_into(&app.disk_info, &mut snapshots, |s| &mut s.disk);
+        copy_into(&app.disk_io_info, &mut snapshots, |s| &mut s.disk_io);
+        copy_into(&app.memory_info, &mut snapshots, |s| &mut s.memory);
+        copy_into(&app.network_info, &mut snapshots, |s| &mut s.network);
+        copy_into(&app.os_info, &mut snapshots, |s| &mut s.os);
+        copy_into(&app.gpu_info, &mut snapshots, |s| &mut s.gpu);
+        copy_into(&app.load_info, &mut snapshots, |s| &mut s.load);
+        copy_into(&app.process_info, &mut snapshots, |s| &mut s.processes);
+        copy_into(&app.sensor_info, &mut snapshots, |s| &mut s.sensors);
+        copy_into(&app.service_info, &mut snapshots, |s| &mut s.services);
+        copy_into(&app.container_info, &mut snapshots, |s| &mut s.containers);
+        copy_into(&app.session_info, &mut snapshots, |s| &mut s.sessions);
+        copy_into(&app.auth_log_info, &mut snapshots, |s| &mut s.auth_log);
+        copy_into(&app.update_info, &mut snapshots, |s| &mut s.updates);
+        copy_into(&app.clock_info, &mut snapshots, |s| &mut s.clock);
+        copy_into(&app.port_info, &mut snapshots, |s| &mut s.ports);
+        snapshots
+    }
+
+    /// Snapshot of a single host; empty when nothing was collected yet.
+    pub fn capture(app: &App, id: &String) -> HostSnapshot {
+        Self::collect(app, [id]).remove(id).unwrap_or_default()
+    }
+
+    pub fn status(&self) -> &SshStatus {
+        self.status.as_ref().unwrap_or(&SshStatus::Loading)
+    }
+}
+
+fn copy_into<T: Clone>(
+    shared: &Mutex<HashMap<String, T>>,
+    snapshots: &mut HashMap<String, HostSnapshot>,
+    field: impl Fn(&mut HostSnapshot) -> &mut Option<T>,
+) {
+    let guard = futures::executor::block_on(shared.lock());
+    for (id, snapshot) in snapshots.iter_mut() {
+        *field(snapshot) = guard.get(id).cloned();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_copies_each_host_and_defaults_missing_ones() {
+        let app = App::new().unwrap();
+        let (web, db) = ("web".to_string(), "db".to_string());
+        futures::executor::block_on(async {
+            app.cpu_info
+                .lock()
+                .await
+                .insert(web.clone(), CpuInfo::Loading);
+            app.ssh_statuses
+                .lock()
+                .await
+                .insert(db.clone(), SshStatus::Failed("timeout".to_string()));
+        });
+
+        let snapshots = HostSnapshot::collect(&app, [&web, &db]);
+        assert_eq!(snapshots.len(), 2);
+        assert!(matches!(snapshots[&web].cpu, Some(CpuInfo::Loading)));
+        assert_eq!(snapshots[&web].status(), &SshStatus::Loading);
+        assert!(snapshots[&db].cpu.is_none());
+        assert_eq!(
+            snapshots[&db].status(),
+            &SshStatus::Failed("timeout".to_string())
+        );
+    }
+}
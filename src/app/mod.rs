@@ -1,59 +1,116 @@
+mod formatting;
+mod gpu_fleet;
+mod headless;
+mod host_snapshot;
 mod ssh_details;
 mod ssh_list;
 mod states;
 use crate::app::states::{
-    SharedCpuInfo, SharedDiskInfo, SharedGpuInfo, SharedMemoryInfo, SharedOsInfo, SharedSshHosts,
-    SharedSshStatuses, SshHostInfo, load_ssh_configs,
+    HistoryStore, HistoryWindow, SharedAppConfig, SharedAuthLogInfo, SharedClockInfo,
+    SharedContainerInfo, SharedCpuInfo, SharedDiskInfo, SharedDiskIoInfo, SharedFocusedHost,
+    SharedGpuInfo, SharedHistory, SharedHistoryStore, SharedLoadInfo, SharedMemoryInfo,
+    SharedNetworkInfo, SharedOsInfo, SharedPortInfo, SharedProcessActionOutput, SharedProcessInfo,
+    SharedSensorInfo, SharedServiceInfo, SharedSessionInfo, SharedSshHosts, SharedSshStatuses,
+    SharedUpdateInfo, SshHostInfo, load_app_config, load_ssh_configs,
 };
 use color_eyre::Result;
 use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind};
 use futures::{FutureExt, StreamExt};
+use gpu_fleet::{handle_key as handle_fleet_key, render as render_fleet};
 use ratatui::prelude::*;
 use ratatui::widgets::TableState;
 use ratatui::{Frame, widgets::ScrollbarState};
-use ssh_details::render as render_detail;
-use ssh_list::{handle_key as handle_list_key, render as render_list};
+use ssh_details::{
+    ProcessDialog, ProcessTable, handle_key as handle_detail_key, render as render_detail,
+};
+use ssh_list::{GroupKey, SortKey, handle_key as handle_list_key, render as render_list};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 mod tasks;
+use tasks::auth_log_task::AuthLogInfoTask;
+use tasks::clock_task::ClockInfoTask;
+use tasks::container_task::ContainerInfoTask;
 use tasks::cpu_status_task::CpuInfoTask;
+use tasks::disk_io_task::DiskIoInfoTask;
 use tasks::disk_task::DiskInfoTask;
 use tasks::executor::TaskExecutor;
 use tasks::gpu_task::GpuInfoTask;
+use tasks::history_task::HistoryCompactTask;
+use tasks::load_task::LoadInfoTask;
 use tasks::memory_task::MemoryInfoTask;
+use tasks::network_task::NetworkInfoTask;
 use tasks::os_task::OsInfoTask;
+use tasks::port_task::PortInfoTask;
+use tasks::process_task::ProcessInfoTask;
+use tasks::sensor_task::SensorInfoTask;
+use tasks::service_task::ServiceInfoTask;
+use tasks::session_task::SessionInfoTask;
 use tasks::ssh_status_task::SshStatusTask;
+use tasks::update_task::UpdateInfoTask;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum AppMode {
     List,
     Detail,
     Search,
+    GpuFleet,
 }
 
 pub struct App {
     running: bool,
-    event_stream: EventStream,
+    pub config: SharedAppConfig,
     pub ssh_hosts: SharedSshHosts,
     pub visible_hosts: Vec<(String, SshHostInfo)>,
     pub ssh_statuses: SharedSshStatuses,
     pub cpu_info: SharedCpuInfo,
     pub disk_info: SharedDiskInfo,
+    pub disk_io_info: SharedDiskIoInfo,
     pub memory_info: SharedMemoryInfo,
+    pub network_info: SharedNetworkInfo,
     pub os_info: SharedOsInfo,
     pub gpu_info: SharedGpuInfo,
+    pub load_info: SharedLoadInfo,
+    pub process_info: SharedProcessInfo,
+    pub sensor_info: SharedSensorInfo,
+    pub service_info: SharedServiceInfo,
+    pub container_info: SharedContainerInfo,
+    pub session_info: SharedSessionInfo,
+    pub auth_log_info: SharedAuthLogInfo,
+    pub update_info: SharedUpdateInfo,
+    pub clock_info: SharedClockInfo,
+    pub port_info: SharedPortInfo,
+    pub history: SharedHistory,
+    pub history_store: SharedHistoryStore,
+    pub history_window: HistoryWindow,
+    pub focused_host: SharedFocusedHost,
     pub selected_id: Option<String>,
     pub search_query: String,
+    pub sort_key: SortKey,
+    pub group_key: GroupKey,
     pub mode: AppMode,
     pub vertical_scroll_state: ScrollbarState,
     pub vertical_scroll: usize,
     pub table_height: usize,
     pub table_state: TableState,
+    pub detail_return_mode: AppMode,
+    pub fleet_rows: Vec<(String, u32)>,
+    pub fleet_table_state: TableState,
+    pub fleet_min_free_gb: u32,
+    pub fleet_idle_minutes: u64,
+    pub process_focus: ProcessTable,
+    pub cpu_process_state: TableState,
+    pub memory_process_state: TableState,
+    pub process_dialog: Option<ProcessDialog>,
+    pub process_action_output: SharedProcessActionOutput,
+    /// Number of process actions confirmed so far, used as request keys.
+    pub process_requests: u64,
 }
 
 impl App {
-    pub fn new() -> Self {
+    pub fn new() -> Result<Self> {
+        let config = load_app_config()?;
+        let history_store = Arc::new(HistoryStore::open(&config.history));
         let ssh_hosts = load_ssh_configs().unwrap_or_default(); // now a HashMap
         let mut visible_hosts: Vec<(String, SshHostInfo)> = ssh_hosts
             .iter()
@@ -62,25 +119,54 @@ impl App {
         visible_hosts.sort_by_key(|(_, h)| h.name.clone());
         let selected_id = visible_hosts.first().map(|(id, _)| id.clone());
 
-        Self {
+        Ok(Self {
+            config: Arc::new(config),
             ssh_hosts: Arc::new(Mutex::new(ssh_hosts)),
             visible_hosts,
             ssh_statuses: Arc::new(Mutex::new(HashMap::new())),
             cpu_info: Arc::new(Mutex::new(HashMap::new())),
             disk_info: Arc::new(Mutex::new(HashMap::new())),
+            disk_io_info: Arc::new(Mutex::new(HashMap::new())),
             memory_info: Arc::new(Mutex::new(HashMap::new())),
+            network_info: Arc::new(Mutex::new(HashMap::new())),
             os_info: Arc::new(Mutex::new(HashMap::new())),
             gpu_info: Arc::new(Mutex::new(HashMap::new())),
+            load_info: Arc::new(Mutex::new(HashMap::new())),
+            process_info: Arc::new(Mutex::new(HashMap::new())),
+            sensor_info: Arc::new(Mutex::new(HashMap::new())),
+            service_info: Arc::new(Mutex::new(HashMap::new())),
+            container_info: Arc::new(Mutex::new(HashMap::new())),
+            session_info: Arc::new(Mutex::new(HashMap::new())),
+            auth_log_info: Arc::new(Mutex::new(HashMap::new())),
+            update_info: Arc::new(Mutex::new(HashMap::new())),
+            clock_info: Arc::new(Mutex::new(HashMap::new())),
+            port_info: Arc::new(Mutex::new(HashMap::new())),
+            history: Arc::new(Mutex::new(history_store.load())),
+            history_store,
+            history_window: HistoryWindow::OneHour,
+            focused_host: Arc::new(Mutex::new(None)),
             running: false,
-            event_stream: EventStream::new(),
             selected_id,
             search_query: String::new(),
+            sort_key: SortKey::Name,
+            group_key: GroupKey::None,
             mode: AppMode::List,
             vertical_scroll_state: ScrollbarState::new(0),
             vertical_scroll: 0,
             table_height: 0,
             table_state: TableState::default().with_selected(Some(0)),
-        }
+            detail_return_mode: AppMode::List,
+            fleet_rows: Vec::new(),
+            fleet_table_state: TableState::default().with_selected(Some(0)),
+            fleet_min_free_gb: 0,
+            fleet_idle_minutes: 0,
+            process_focus: ProcessTable::Cpu,
+            cpu_process_state: TableState::default().with_selected(Some(0)),
+            memory_process_state: TableState::default().with_selected(Some(0)),
+            process_dialog: None,
+            process_action_output: Arc::new(Mutex::new(HashMap::new())),
+            process_requests: 0,
+        })
     }
 
     pub async fn run(
@@ -88,7 +174,21 @@ impl App {
         mut terminal: Terminal<CrosstermBackend<std::io::Stdout>>,
     ) -> Result<()> {
         self.running = true;
+        self.start_tasks();
+        // Created here rather than in `new` since it needs a terminal
+        let mut events = EventStream::new();
+
+        while self.running {
+            self.sync_focused_host().await;
+            terminal.draw(|frame| self.draw(frame))?;
+            self.handle_crossterm_events(&mut events).await?;
+        }
+
+        Ok(())
+    }
 
+    /// Starts every background collector against the shared state.
+    fn start_tasks(&self) {
         let mut executor = TaskExecutor::new();
         executor.register(SshStatusTask {
             ssh_hosts: Arc::clone(&self.ssh_hosts),
@@ -97,14 +197,23 @@ impl App {
         executor.register(CpuInfoTask {
             ssh_hosts: Arc::clone(&self.ssh_hosts),
             cpu_info: Arc::clone(&self.cpu_info),
+            history: Arc::clone(&self.history),
+            history_store: Arc::clone(&self.history_store),
         });
         executor.register(DiskInfoTask {
             ssh_hosts: Arc::clone(&self.ssh_hosts),
             disk_info: Arc::clone(&self.disk_info),
+            config: Arc::clone(&self.config),
+        });
+        executor.register(DiskIoInfoTask {
+            ssh_hosts: Arc::clone(&self.ssh_hosts),
+            disk_io_info: Arc::clone(&self.disk_io_info),
         });
         executor.register(MemoryInfoTask {
             ssh_hosts: Arc::clone(&self.ssh_hosts),
             memory_info: Arc::clone(&self.memory_info),
+            history: Arc::clone(&self.history),
+            history_store: Arc::clone(&self.history_store),
         });
         executor.register(OsInfoTask {
             ssh_hosts: Arc::clone(&self.ssh_hosts),
@@ -113,32 +222,81 @@ impl App {
         executor.register(GpuInfoTask {
             ssh_hosts: Arc::clone(&self.ssh_hosts),
             gpu_info: Arc::clone(&self.gpu_info),
+            history: Arc::clone(&self.history),
+            history_store: Arc::clone(&self.history_store),
+        });
+        executor.register(LoadInfoTask {
+            ssh_hosts: Arc::clone(&self.ssh_hosts),
+            load_info: Arc::clone(&self.load_info),
+        });
+        executor.register(NetworkInfoTask {
+            ssh_hosts: Arc::clone(&self.ssh_hosts),
+            network_info: Arc::clone(&self.network_info),
+        });
+        executor.register(SensorInfoTask {
+            ssh_hosts: Arc::clone(&self.ssh_hosts),
+            sensor_info: Arc::clone(&self.sensor_info),
+        });
+        executor.register(ServiceInfoTask {
+            ssh_hosts: Arc::clone(&self.ssh_hosts),
+            service_info: Arc::clone(&self.service_info),
+            config: Arc::clone(&self.config),
+        });
+        executor.register(ContainerInfoTask {
+            ssh_hosts: Arc::clone(&self.ssh_hosts),
+            container_info: Arc::clone(&self.container_info),
+        });
+        executor.register(SessionInfoTask {
+            ssh_hosts: Arc::clone(&self.ssh_hosts),
+            session_info: Arc::clone(&self.session_info),
+            config: Arc::clone(&self.config),
+        });
+        executor.register(AuthLogInfoTask {
+            ssh_hosts: Arc::clone(&self.ssh_hosts),
+            auth_log_info: Arc::clone(&self.auth_log_info),
+            config: Arc::clone(&self.config),
+        });
+        executor.register(UpdateInfoTask {
+            ssh_hosts: Arc::clone(&self.ssh_hosts),
+            update_info: Arc::clone(&self.update_info),
+        });
+        executor.register(ClockInfoTask {
+            ssh_hosts: Arc::clone(&self.ssh_hosts),
+            clock_info: Arc::clone(&self.clock_info),
+            config: Arc::clone(&self.config),
+        });
+        executor.register(PortInfoTask {
+            ssh_hosts: Arc::clone(&self.ssh_hosts),
+            port_info: Arc::clone(&self.port_info),
+            config: Arc::clone(&self.config),
+        });
+        executor.register(HistoryCompactTask {
+            history_store: Arc::clone(&self.history_store),
+        });
+        executor.register(ProcessInfoTask {
+            ssh_hosts: Arc::clone(&self.ssh_hosts),
+            process_info: Arc::clone(&self.process_info),
+            focused_host: Arc::clone(&self.focused_host),
+            last_full_refresh: Mutex::new(None),
         });
         executor.start();
-
-        while self.running {
-            terminal.draw(|frame| self.draw(frame))?;
-            self.handle_crossterm_events().await?;
-        }
-
-        Ok(())
     }
 
     fn draw(&mut self, frame: &mut Frame) {
         match self.mode {
             AppMode::List | AppMode::Search => render_list(self, frame),
             AppMode::Detail => render_detail(self, frame),
+            AppMode::GpuFleet => render_fleet(self, frame),
         }
     }
 
-    async fn handle_crossterm_events(&mut self) -> Result<()> {
+    async fn handle_crossterm_events(&mut self, events: &mut EventStream) -> Result<()> {
         tokio::select! {
-            event = self.event_stream.next().fuse() => {
-                if let Some(Ok(Event::Key(key))) = event {
-                    if key.kind == KeyEventKind::Press {
+            event = events.next().fuse() => {
+                if let Some(Ok(Event::Key(key))) = event
+                    && key.kind == KeyEventKind::Press {
                         self.on_key_event(key);
                     }
-                }
             }
             _ = tokio::time::sleep(tokio::time::Duration::from_millis(100)) => {}
         }
@@ -152,6 +310,19 @@ impl App {
                     self.mode = AppMode::Search;
                     self.search_query.clear();
                 }
+                KeyCode::Char('s') => {
+                    self.sort_key = self.sort_key.next();
+                }
+                KeyCode::Char('G') => {
+                    self.group_key = self.group_key.next();
+                }
+                KeyCode::Char('g') => {
+                    self.mode = AppMode::GpuFleet;
+                }
+                KeyCode::Enter => {
+                    self.detail_return_mode = AppMode::List;
+                    handle_list_key(self, key);
+                }
                 KeyCode::Char('j') | KeyCode::Down => {
                     self.vertical_scroll = self.vertical_scroll.saturating_add(1);
                     handle_list_key(self, key);
@@ -182,20 +353,34 @@ impl App {
 
                 _ => {}
             },
-            AppMode::Detail => {
-                if key.code == KeyCode::Esc || key.code == KeyCode::Char('q') {
-                    self.mode = AppMode::List;
-                }
-            }
+            AppMode::Detail => handle_detail_key(self, key),
+            AppMode::GpuFleet => handle_fleet_key(self, key),
+        }
+    }
+
+    /// Publishes the host whose detail view is open so its processes are
+    /// refreshed quickly, and resets the process tables when it changes.
+    async fn sync_focused_host(&mut self) {
+        let focused = match self.mode {
+            AppMode::Detail => self.selected_id.clone(),
+            _ => None,
+        };
+        let mut current = self.focused_host.lock().await;
+        if *current != focused {
+            *current = focused;
+            self.process_focus = ProcessTable::Cpu;
+            self.cpu_process_state.select(Some(0));
+            self.memory_process_state.select(Some(0));
+            self.process_dialog = None;
         }
     }
 
     pub fn update_selected_id_from_table(&mut self) {
-        if let Some(index) = self.table_state.selected() {
-            if index < self.visible_hosts.len() {
-                let (id, _) = &self.visible_hosts[index];
-                self.selected_id = Some(id.clone());
-            }
+        if let Some(index) = self.table_state.selected()
+            && index < self.visible_hosts.len()
+        {
+            let (id, _) = &self.visible_hosts[index];
+            self.selected_id = Some(id.clone());
         }
     }
 }
@@ -1,3 +1,5 @@
+pub mod update;
 pub mod view;
 
+pub use update::{ProcessDialog, ProcessTable, handle_key};
 pub use view::render;
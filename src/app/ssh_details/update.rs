@@ -0,0 +1,197 @@
+use crate::app::App;
+use crate::app::states::{
+    NICE_MAX, NICE_MIN, ProcessAction, ProcessEntry, ProcessInfo, run_process_action,
+};
+use crossterm::event::KeyCode;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::{task, time::timeout};
+
+/// Which of the two process tables receives scroll keys.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProcessTable {
+    Cpu,
+    Memory,
+}
+
+/// Popup shown over the detail view while acting on a process.
+#[derive(Debug, Clone)]
+pub enum ProcessDialog {
+    Confirm {
+        action: ProcessAction,
+        process: ProcessEntry,
+        use_sudo: bool,
+    },
+    Output {
+        title: String,
+        /// Key of this action's result in `process_action_output`.
+        request: u64,
+        scroll: u16,
+    },
+}
+
+pub fn handle_key(app: &mut App, key: crossterm::event::KeyEvent) {
+    if app.process_dialog.is_some() {
+        handle_dialog_key(app, key);
+        return;
+    }
+
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => {
+            app.mode = app.detail_return_mode.clone();
+        }
+        KeyCode::Tab | KeyCode::Left | KeyCode::Right => {
+            app.process_focus = match app.process_focus {
+                ProcessTable::Cpu => ProcessTable::Memory,
+                ProcessTable::Memory => ProcessTable::Cpu,
+            };
+        }
+        KeyCode::Char('j') | KeyCode::Down => move_selection(app, 1),
+        KeyCode::Char('k') | KeyCode::Up => move_selection(app, -1),
+        KeyCode::PageDown => move_selection(app, 10),
+        KeyCode::PageUp => move_selection(app, -10),
+        KeyCode::Char('t') => open_confirm(app, ProcessAction::Terminate),
+        KeyCode::Char('K') => open_confirm(app, ProcessAction::Kill),
+        KeyCode::Char('n') => open_confirm(app, ProcessAction::Renice(10)),
+        KeyCode::Char('e') => open_confirm(app, ProcessAction::Environment),
+        KeyCode::Char('o') => open_confirm(app, ProcessAction::OpenFiles),
+        KeyCode::Char('w') => app.history_window = app.history_window.next(),
+        _ => {}
+    }
+}
+
+fn handle_dialog_key(app: &mut App, key: crossterm::event::KeyEvent) {
+    let Some(dialog) = app.process_dialog.as_mut() else {
+        return;
+    };
+
+    match dialog {
+        ProcessDialog::Confirm {
+            action, use_sudo, ..
+        } => match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => confirm_action(app),
+            KeyCode::Char('n') | KeyCode::Char('q') | KeyCode::Esc => app.process_dialog = None,
+            KeyCode::Char('s') => *use_sudo = !*use_sudo,
+            KeyCode::Char('+') | KeyCode::Up => {
+                if let ProcessAction::Renice(nice) = action {
+                    *nice = (*nice + 1).min(NICE_MAX);
+                }
+            }
+            KeyCode::Char('-') | KeyCode::Down => {
+                if let ProcessAction::Renice(nice) = action {
+                    *nice = (*nice - 1).max(NICE_MIN);
+                }
+            }
+            _ => {}
+        },
+        ProcessDialog::Output { scroll, .. } => match key.code {
+            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter => app.process_dialog = None,
+            KeyCode::Char('j') | KeyCode::Down => *scroll = scroll.saturating_add(1),
+            KeyCode::Char('k') | KeyCode::Up => *scroll = scroll.saturating_sub(1),
+            KeyCode::PageDown => *scroll = scroll.saturating_add(10),
+            KeyCode::PageUp => *scroll = scroll.saturating_sub(10),
+            _ => {}
+        },
+    }
+}
+
+/// Asks for confirmation before acting on the selected process. Processes
+/// owned by someone other than the SSH user default to going through sudo.
+fn open_confirm(app: &mut App, action: ProcessAction) {
+    let Some(process) = selected_process(app) else {
+        return;
+    };
+    let login_user = app.selected_id.as_ref().and_then(|id| {
+        let hosts = futures::executor::block_on(app.ssh_hosts.lock());
+        hosts.get(id).map(|h| h.user.clone())
+    });
+    let use_sudo = login_user.is_some_and(|user| user != process.user);
+
+    app.process_dialog = Some(ProcessDialog::Confirm {
+        action,
+        process,
+        use_sudo,
+    });
+}
+
+fn confirm_action(app: &mut App) {
+    let Some(ProcessDialog::Confirm {
+        action,
+        process,
+        use_sudo,
+    }) = app.process_dialog.take()
+    else {
+        return;
+    };
+    let Some(info) = app.selected_id.as_ref().and_then(|id| {
+        let hosts = futures::executor::block_on(app.ssh_hosts.lock());
+        hosts.get(id).cloned()
+    }) else {
+        return;
+    };
+
+    app.process_requests += 1;
+    let request = app.process_requests;
+    app.process_dialog = Some(ProcessDialog::Output {
+        title: format!("{} [{}] on {}", action.label(), process.pid, info.name),
+        request,
+        scroll: 0,
+    });
+
+    // Results of earlier requests are no longer shown
+    futures::executor::block_on(app.process_action_output.lock()).clear();
+    let output = Arc::clone(&app.process_action_output);
+    tokio::spawn(async move {
+        let result = timeout(
+            Duration::from_secs(10),
+            task::spawn_blocking(move || run_process_action(&info, &process, &action, use_sudo)),
+        )
+        .await;
+
+        let action_result = match result {
+            Ok(Ok(result)) => result,
+            Ok(Err(e)) => Err(format!("Thread error: {e}")),
+            Err(_) => Err("Timed out".to_string()),
+        };
+
+        output.lock().await.insert(request, action_result);
+    });
+}
+
+/// Process under the cursor in whichever table has focus.
+fn selected_process(app: &App) -> Option<ProcessEntry> {
+    let processes = futures::executor::block_on(app.process_info.lock());
+    let Some(ProcessInfo::Success { by_cpu, by_memory }) =
+        app.selected_id.as_ref().and_then(|id| processes.get(id))
+    else {
+        return None;
+    };
+    match app.process_focus {
+        ProcessTable::Cpu => by_cpu.get(app.cpu_process_state.selected()?).cloned(),
+        ProcessTable::Memory => by_memory.get(app.memory_process_state.selected()?).cloned(),
+    }
+}
+
+fn move_selection(app: &mut App, delta: isize) {
+    let len = {
+        let processes = futures::executor::block_on(app.process_info.lock());
+        match app.selected_id.as_ref().and_then(|id| processes.get(id)) {
+            Some(ProcessInfo::Success { by_cpu, by_memory }) => match app.process_focus {
+                ProcessTable::Cpu => by_cpu.len(),
+                ProcessTable::Memory => by_memory.len(),
+            },
+            _ => 0,
+        }
+    };
+    if len == 0 {
+        return;
+    }
+
+    let state = match app.process_focus {
+        ProcessTable::Cpu => &mut app.cpu_process_state,
+        ProcessTable::Memory => &mut app.memory_process_state,
+    };
+    let current = state.selected().unwrap_or(0) as isize;
+    let next = (current + delta).clamp(0, len as isize - 1);
+    state.select(Some(next as usize));
+}
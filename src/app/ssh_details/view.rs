@@ -1,39 +1,187 @@
+use super::update::{ProcessDialog, ProcessTable};
 use crate::app::App;
-use crate::app::states::{CpuInfo, DiskInfo, GpuInfo, MemoryInfo, OsInfo, SshStatus};
+use crate::app::formatting::{format_bytes, format_uptime};
+use crate::app::host_snapshot::HostSnapshot;
+use crate::app::states::{
+    AuthLogInfo, ClockInfo, ContainerInfo, CpuInfo, DiskInfo, DiskIoInfo, FanReading, GpuInfo,
+    LoadInfo, MemoryInfo, Metric, NO_CONTAINER_RUNTIME, NO_GPU_FOUND, NO_PACKAGE_MANAGER,
+    NO_SENSORS_FOUND, NO_SYSTEMD, NetworkInfo, OsInfo, PortInfo, ProcessAction, ProcessEntry,
+    ProcessInfo, SensorInfo, SensorKind, ServiceInfo, SessionInfo, SshStatus, TemperatureReading,
+    UpdateInfo, unix_now,
+};
 use ratatui::prelude::*;
 use ratatui::widgets::*;
 
+/// Rows kept for the process tables and the command line below them.
+const PROCESS_MIN_HEIGHT: u16 = 11;
+
 pub fn render(app: &mut App, frame: &mut Frame) {
     let area = frame.area();
 
-    let hosts_guard = futures::executor::block_on(app.ssh_hosts.lock());
-    let status_guard = futures::executor::block_on(app.ssh_statuses.lock());
-    let cpu_guard = futures::executor::block_on(app.cpu_info.lock());
-    let disk_guard = futures::executor::block_on(app.disk_info.lock());
-    let memory_guard = futures::executor::block_on(app.memory_info.lock());
-    let os_guard = futures::executor::block_on(app.os_info.lock());
-    let gpu_guard = futures::executor::block_on(app.gpu_info.lock());
-
-    let host = app.selected_id.as_ref().and_then(|id| hosts_guard.get(id));
-
-    let host_name = host
-        .map(|h| h.name.clone())
+    let host_name = app
+        .selected_id
+        .as_ref()
+        .and_then(|id| {
+            let hosts = futures::executor::block_on(app.ssh_hosts.lock());
+            hosts.get(id).map(|h| h.name.clone())
+        })
         .unwrap_or_else(|| "<none>".to_string());
-
-    let status = app
+    let host = app
         .selected_id
         .as_ref()
-        .and_then(|id| status_guard.get(id))
-        .cloned()
-        .unwrap_or(SshStatus::Loading);
+        .map(|id| HostSnapshot::capture(app, id))
+        .unwrap_or_default();
+
+    // Borders + header row + one row per device (or per process, capped)
+    let gpu_height = match &host.gpu {
+        Some(GpuInfo::Success { devices }) => {
+            let process_count: usize = devices.iter().map(|d| d.processes.len()).sum();
+            devices.len().max(process_count.min(10)) as u16 + 3
+        }
+        _ => 3,
+    };
+    let disk_rows = match &host.disk {
+        Some(DiskInfo::Success { filesystems }) => filesystems.len(),
+        _ => 0,
+    };
+    let disk_io_rows = match &host.disk_io {
+        Some(DiskIoInfo::Success { devices }) => devices.len(),
+        _ => 0,
+    };
+    let disk_height = disk_rows.max(disk_io_rows).min(8) as u16 + 3;
+    let sensor_rows = match &host.sensors {
+        Some(SensorInfo::Success { temperatures, fans }) => {
+            sensor_table_rows(temperatures, fans).len()
+        }
+        _ => 0,
+    };
+    let network_rows = match &host.network {
+        Some(NetworkInfo::Success { interfaces }) => interfaces.len(),
+        _ => 0,
+    };
+    let port_rows = match &host.ports {
+        Some(PortInfo::Success { ports }) => ports.len(),
+        _ => 0,
+    };
+    let network_height = network_rows.max(sensor_rows).max(port_rows).min(8) as u16 + 3;
+    let container_rows = match &host.containers {
+        Some(ContainerInfo::Success { containers, .. }) => containers.len() + 1,
+        _ => 0,
+    };
+    let services_height = service_lines(host.services.as_ref())
+        .len()
+        .max(container_rows)
+        .max(update_lines(host.updates.as_ref()).len())
+        .min(10) as u16
+        + 2;
+    let session_rows = match &host.sessions {
+        Some(SessionInfo::Success { sessions }) => sessions.len(),
+        _ => 0,
+    };
+    let offender_rows = match &host.auth_log {
+        Some(AuthLogInfo::Success { offenders, .. }) => offenders.len(),
+        _ => 0,
+    };
+    let os_height = match &host.os {
+        Some(OsInfo::Success { .. }) => 9,
+        _ => 4,
+    }
+    .max(session_rows.max(offender_rows).min(6) as u16 + 3);
+
+    // The panels shrink before the process tables drop below their minimum
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(9),
+            Constraint::Max(gpu_height),
+            Constraint::Max(disk_height),
+            Constraint::Max(network_height),
+            Constraint::Max(services_height),
+            Constraint::Max(os_height),
+            Constraint::Min(PROCESS_MIN_HEIGHT),
+        ])
+        .split(area);
+
+    render_header(frame, chunks[0], &host_name, host.status());
+
+    let top_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(18),
+            Constraint::Percentage(30),
+            Constraint::Percentage(22),
+            Constraint::Percentage(30),
+        ])
+        .split(chunks[1]);
+    render_cpu(frame, top_chunks[0], host.cpu.as_ref());
+    render_memory(frame, top_chunks[1], host.memory.as_ref());
+    render_load(frame, top_chunks[2], host.load.as_ref(), host.cpu.as_ref());
+    render_history(app, frame, top_chunks[3]);
+
+    render_gpu(frame, chunks[2], host.gpu.as_ref());
+
+    let disk_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(chunks[3]);
+    render_disk(frame, disk_chunks[0], host.disk.as_ref());
+    render_disk_io(frame, disk_chunks[1], host.disk_io.as_ref());
+
+    let net_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(45),
+            Constraint::Percentage(30),
+            Constraint::Percentage(25),
+        ])
+        .split(chunks[4]);
+    render_network(frame, net_chunks[0], host.network.as_ref());
+    render_ports(frame, net_chunks[1], host.ports.as_ref());
+    render_sensors(frame, net_chunks[2], host.sensors.as_ref());
 
-    let cpu = app.selected_id.as_ref().and_then(|id| cpu_guard.get(id));
-    let disk = app.selected_id.as_ref().and_then(|id| disk_guard.get(id));
-    let memory = app.selected_id.as_ref().and_then(|id| memory_guard.get(id));
-    let os = app.selected_id.as_ref().and_then(|id| os_guard.get(id));
-    let gpu = app.selected_id.as_ref().and_then(|id| gpu_guard.get(id));
+    let service_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(30),
+            Constraint::Percentage(25),
+            Constraint::Percentage(45),
+        ])
+        .split(chunks[5]);
+    let services_block = Paragraph::new(service_lines(host.services.as_ref())).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("⚙ systemd Units"),
+    );
+    frame.render_widget(services_block, service_chunks[0]);
+    let updates_block = Paragraph::new(update_lines(host.updates.as_ref()))
+        .wrap(Wrap { trim: true })
+        .block(Block::default().borders(Borders::ALL).title("📦 Updates"));
+    frame.render_widget(updates_block, service_chunks[1]);
+    render_containers(frame, service_chunks[2], host.containers.as_ref());
 
-    let (status_text, status_style, status_msg) = match &status {
+    let os_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(25),
+            Constraint::Percentage(40),
+            Constraint::Percentage(35),
+        ])
+        .split(chunks[6]);
+    render_os(frame, os_chunks[0], host.os.as_ref(), host.clock.as_ref());
+    render_sessions(frame, os_chunks[1], host.sessions.as_ref());
+    render_auth_log(frame, os_chunks[2], host.auth_log.as_ref());
+
+    render_processes(app, frame, chunks[7], host.processes.as_ref());
+
+    if let Some(dialog) = &app.process_dialog {
+        render_process_dialog(app, dialog, frame);
+    }
+}
+
+fn render_header(frame: &mut Frame, area: Rect, host_name: &str, status: &SshStatus) {
+    let (status_text, status_style, status_msg) = match status {
         SshStatus::Connected => (
             "🟢 Connected".to_string(),
             Style::default().fg(Color::Green),
@@ -68,27 +216,10 @@ pub fn render(app: &mut App, frame: &mut Frame) {
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::ALL));
 
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .margin(1)
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Length(7),
-            Constraint::Length(3),
-            Constraint::Length(3),
-            Constraint::Length(3),
-            Constraint::Min(0),
-        ])
-        .split(area);
-
-    frame.render_widget(header, chunks[0]);
-
-    let top_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(chunks[1]);
+    frame.render_widget(header, area);
+}
 
-    // CPU INFO BLOCK
+fn render_cpu(frame: &mut Frame, area: Rect, cpu: Option<&CpuInfo>) {
     let cpu_lines: Vec<Line> = match cpu {
         Some(CpuInfo::Success {
             core_count,
@@ -107,18 +238,48 @@ pub fn render(app: &mut App, frame: &mut Frame) {
 
     let cpu_block = Paragraph::new(cpu_lines)
         .block(Block::default().borders(Borders::ALL).title("🧠 CPU Usage"));
-    frame.render_widget(cpu_block, top_chunks[0]);
+    frame.render_widget(cpu_block, area);
+}
 
-    // MEMORY INFO
+fn render_memory(frame: &mut Frame, area: Rect, memory: Option<&MemoryInfo>) {
     let mem_lines: Vec<Line> = match memory {
-        Some(MemoryInfo::Success {
-            total,
-            used,
-            usage_percent,
-        }) => vec![
-            Line::raw(format!("Total: {}", total)),
-            Line::raw(format!("Used: {} ({})", used, usage_percent)),
-        ],
+        Some(MemoryInfo::Success { stats }) => {
+            let mut lines = vec![
+                Line::raw(format!(
+                    "Used: {} / {} ({:.1}%)",
+                    format_bytes(stats.used_bytes),
+                    format_bytes(stats.total_bytes),
+                    stats.usage_percent()
+                )),
+                Line::raw(format!(
+                    "Available: {}",
+                    format_bytes(stats.available_bytes)
+                )),
+                Line::raw(format!(
+                    "Buffers/cache: {}",
+                    format_bytes(stats.buffers_cache_bytes)
+                )),
+            ];
+            if stats.swap_total_bytes > 0 {
+                lines.push(Line::raw(format!(
+                    "Swap: {} / {} ({:.1}%)",
+                    format_bytes(stats.swap_used_bytes),
+                    format_bytes(stats.swap_total_bytes),
+                    stats.swap_usage_percent()
+                )));
+            } else {
+                lines.push(Line::raw("Swap: none"));
+            }
+            if stats.huge_pages_total > 0 {
+                lines.push(Line::raw(format!(
+                    "Huge pages: {} / {} free ({} each)",
+                    stats.huge_pages_free,
+                    stats.huge_pages_total,
+                    format_bytes(stats.huge_page_size_bytes)
+                )));
+            }
+            lines
+        }
         Some(MemoryInfo::Failure(e)) => vec![Line::styled(
             format!("Error: {e}"),
             Style::default().fg(Color::Red),
@@ -129,73 +290,611 @@ pub fn render(app: &mut App, frame: &mut Frame) {
 
     let mem_block = Paragraph::new(mem_lines)
         .block(Block::default().borders(Borders::ALL).title("Memory Usage"));
-    frame.render_widget(mem_block, top_chunks[1]);
-
-    // GPU INFO
-    let gpu_lines: Vec<Line> = match gpu {
-        Some(GpuInfo::Success {
-            name,
-            memory_total_mb,
-            memory_used_mb,
-            utilization_percent,
-            temperature_c,
-        }) => vec![
-            Line::raw(format!("GPU: {}", name)),
-            Line::raw(format!("Util: {}%", utilization_percent)),
-            Line::raw(format!("Mem: {}/{}MB", memory_used_mb, memory_total_mb)),
-            Line::raw(format!("{}°C", temperature_c)),
-        ],
-        Some(GpuInfo::Failure(e)) => {
-            if e == "nvidia-smi not available" {
-                vec![Line::raw("N/A")]
-            } else {
-                vec![Line::styled(
-                    format!("Error: {e}"),
-                    Style::default().fg(Color::Red),
-                )]
+    frame.render_widget(mem_block, area);
+}
+
+/// Load averages, also per core once the CPU collector reported a core count.
+fn render_load(frame: &mut Frame, area: Rect, load: Option<&LoadInfo>, cpu: Option<&CpuInfo>) {
+    let core_count = match cpu {
+        Some(CpuInfo::Success { core_count, .. }) if *core_count > 0 => Some(*core_count),
+        _ => None,
+    };
+    let load_lines: Vec<Line> = match load {
+        Some(LoadInfo::Success {
+            load_1,
+            load_5,
+            load_15,
+            uptime_secs,
+            running_procs,
+            total_procs,
+            user_count,
+        }) => {
+            let mut lines = vec![Line::raw(format!(
+                "Load: {load_1:.2} {load_5:.2} {load_15:.2}"
+            ))];
+            if let Some(cores) = core_count {
+                let cores = cores as f32;
+                lines.push(Line::raw(format!(
+                    "Per core: {:.2} {:.2} {:.2}",
+                    load_1 / cores,
+                    load_5 / cores,
+                    load_15 / cores
+                )));
             }
+            lines.push(Line::raw(format!(
+                "Uptime: {}",
+                format_uptime(*uptime_secs)
+            )));
+            lines.push(Line::raw(format!(
+                "Procs: {running_procs} running / {total_procs} total"
+            )));
+            lines.push(Line::raw(format!("Users: {user_count}")));
+            lines
         }
-        Some(GpuInfo::Loading) => vec![Line::raw("Loading...")],
-        None => vec![Line::raw("N/A")],
-    };
-    let gpu_block =
-        Paragraph::new(gpu_lines).block(Block::default().borders(Borders::ALL).title("🔧 GPU"));
-    frame.render_widget(gpu_block, chunks[2]);
-
-    // DISK INFO
-    let disk_lines: Vec<Line> = match disk {
-        Some(DiskInfo::Success {
-            total,
-            used,
-            avail,
-            usage_percent,
-        }) => vec![
-            Line::raw(format!("Total: {}", total)),
-            Line::raw(format!("Used: {}", used)),
-            Line::raw(format!("Avail: {}", avail)),
-            Line::raw(format!("Usage: {}", usage_percent)),
-        ],
-        Some(DiskInfo::Failure(e)) => vec![Line::styled(
+        Some(LoadInfo::Failure(e)) => vec![Line::styled(
             format!("Error: {e}"),
             Style::default().fg(Color::Red),
         )],
-        Some(DiskInfo::Loading) => vec![Line::raw("Loading...")],
+        Some(LoadInfo::Loading) => vec![Line::raw("Loading...")],
         None => vec![Line::raw("N/A")],
     };
-    let disk_block = Paragraph::new(disk_lines)
-        .block(Block::default().borders(Borders::ALL).title("Disk Usage"));
-    frame.render_widget(disk_block, chunks[3]);
 
-    // OS INFO
+    let load_block = Paragraph::new(load_lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Load & Uptime"),
+    );
+    frame.render_widget(load_block, area);
+}
+
+/// CPU, memory and GPU history of the selected host over `app.history_window`.
+fn render_history(app: &App, frame: &mut Frame, area: Rect) {
+    let history_guard = futures::executor::block_on(app.history.lock());
+    let window = app.history_window;
+    let now = unix_now();
+    let history = app
+        .selected_id
+        .as_ref()
+        .and_then(|id| history_guard.get(id));
+    let series: Vec<_> = [
+        ("CPU", Color::Cyan, Metric::Cpu),
+        ("Mem", Color::Magenta, Metric::Memory),
+        ("GPU", Color::Green, Metric::Gpu),
+    ]
+    .into_iter()
+    .filter_map(|(name, color, metric)| {
+        let points = history.map(|h| h.get(metric).points(now, window))?;
+        (!points.is_empty()).then_some((name, color, points))
+    })
+    .collect();
+    let datasets = series
+        .iter()
+        .map(|(name, color, points)| {
+            Dataset::default()
+                .name(*name)
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(*color))
+                .data(points)
+        })
+        .collect();
+
+    let history_chart = Chart::new(datasets)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("📈 History {} (w: window)", window.label())),
+        )
+        .x_axis(
+            Axis::default()
+                .bounds([-(window.secs() as f64), 0.0])
+                .labels([format!("-{}", window.label()), "now".to_string()]),
+        )
+        .y_axis(Axis::default().bounds([0.0, 100.0]).labels(["0%", "100%"]))
+        .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)));
+    frame.render_widget(history_chart, area);
+}
+
+fn render_gpu(frame: &mut Frame, area: Rect, gpu: Option<&GpuInfo>) {
+    match gpu {
+        Some(GpuInfo::Success { devices }) => {
+            let has_processes = devices.iter().any(|d| !d.processes.is_empty());
+            let gpu_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(if has_processes {
+                    vec![Constraint::Percentage(60), Constraint::Percentage(40)]
+                } else {
+                    vec![Constraint::Percentage(100)]
+                })
+                .split(area);
+
+            let header = Row::new(vec![
+                "#", "VENDOR", "NAME", "UTIL", "MEM", "TEMP", "POWER", "FAN", "UUID",
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+            let rows = devices.iter().map(|d| {
+                Row::new(vec![
+                    d.index.to_string(),
+                    d.vendor.to_string(),
+                    d.name.clone(),
+                    format!("{}%", d.utilization_percent),
+                    format!("{}/{}MB", d.memory_used_mb, d.memory_total_mb),
+                    format!("{}°C", d.temperature_c),
+                    d.power_draw_w
+                        .map(|w| format!("{w:.0}W"))
+                        .unwrap_or_else(|| "-".into()),
+                    d.fan_percent
+                        .map(|f| format!("{f}%"))
+                        .unwrap_or_else(|| "-".into()),
+                    d.uuid.clone(),
+                ])
+            });
+            let gpu_table = Table::new(
+                rows,
+                [
+                    Constraint::Length(3),
+                    Constraint::Length(7),
+                    Constraint::Length(24),
+                    Constraint::Length(6),
+                    Constraint::Length(14),
+                    Constraint::Length(6),
+                    Constraint::Length(7),
+                    Constraint::Length(5),
+                    Constraint::Min(10),
+                ],
+            )
+            .header(header)
+            .block(Block::default().borders(Borders::ALL).title("🔧 GPU"));
+            frame.render_widget(gpu_table, gpu_chunks[0]);
+
+            if has_processes {
+                let proc_header = Row::new(vec!["GPU", "PID", "USER", "MEM", "COMMAND"])
+                    .style(Style::default().add_modifier(Modifier::BOLD));
+                let proc_rows = devices.iter().flat_map(|d| {
+                    d.processes.iter().map(move |p| {
+                        Row::new(vec![
+                            d.index.to_string(),
+                            p.pid.to_string(),
+                            p.user.clone(),
+                            format!("{}MB", p.used_memory_mb),
+                            p.command.clone(),
+                        ])
+                    })
+                });
+                let proc_table = Table::new(
+                    proc_rows,
+                    [
+                        Constraint::Length(3),
+                        Constraint::Length(7),
+                        Constraint::Length(10),
+                        Constraint::Length(8),
+                        Constraint::Min(10),
+                    ],
+                )
+                .header(proc_header)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title("GPU Processes"),
+                );
+                frame.render_widget(proc_table, gpu_chunks[1]);
+            }
+        }
+        other => {
+            let gpu_lines: Vec<Line> = match other {
+                Some(GpuInfo::Failure(e)) if e == NO_GPU_FOUND => {
+                    vec![Line::raw("N/A")]
+                }
+                Some(GpuInfo::Failure(e)) => vec![Line::styled(
+                    format!("Error: {e}"),
+                    Style::default().fg(Color::Red),
+                )],
+                Some(GpuInfo::Loading) => vec![Line::raw("Loading...")],
+                _ => vec![Line::raw("N/A")],
+            };
+            let gpu_block = Paragraph::new(gpu_lines)
+                .block(Block::default().borders(Borders::ALL).title("🔧 GPU"));
+            frame.render_widget(gpu_block, area);
+        }
+    }
+}
+
+fn render_disk(frame: &mut Frame, area: Rect, disk: Option<&DiskInfo>) {
+    match disk {
+        Some(DiskInfo::Success { filesystems }) => {
+            let header = Row::new(vec![
+                "MOUNT", "DEVICE", "TYPE", "SIZE", "USED", "AVAIL", "USE%", "INODES",
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+            let rows = filesystems.iter().map(|fs| {
+                let usage = fs.usage_percent();
+                let style = if usage >= 90.0 {
+                    Style::default().fg(Color::Red)
+                } else {
+                    Style::default()
+                };
+                Row::new(vec![
+                    fs.mount_point.clone(),
+                    fs.device.clone(),
+                    fs.fs_type.clone(),
+                    format_bytes(fs.total_bytes),
+                    format_bytes(fs.used_bytes),
+                    format_bytes(fs.avail_bytes),
+                    format!("{usage:.1}%"),
+                    fs.inode_usage_percent()
+                        .map(|p| format!("{p:.1}%"))
+                        .unwrap_or_else(|| "-".into()),
+                ])
+                .style(style)
+            });
+            let disk_table = Table::new(
+                rows,
+                [
+                    Constraint::Min(12),
+                    Constraint::Length(20),
+                    Constraint::Length(8),
+                    Constraint::Length(10),
+                    Constraint::Length(10),
+                    Constraint::Length(10),
+                    Constraint::Length(6),
+                    Constraint::Length(7),
+                ],
+            )
+            .header(header)
+            .block(Block::default().borders(Borders::ALL).title("Disk Usage"));
+            frame.render_widget(disk_table, area);
+        }
+        other => {
+            let disk_lines: Vec<Line> = match other {
+                Some(DiskInfo::Failure(e)) => vec![Line::styled(
+                    format!("Error: {e}"),
+                    Style::default().fg(Color::Red),
+                )],
+                Some(DiskInfo::Loading) => vec![Line::raw("Loading...")],
+                _ => vec![Line::raw("N/A")],
+            };
+            let disk_block = Paragraph::new(disk_lines)
+                .block(Block::default().borders(Borders::ALL).title("Disk Usage"));
+            frame.render_widget(disk_block, area);
+        }
+    }
+}
+
+fn render_disk_io(frame: &mut Frame, area: Rect, disk_io: Option<&DiskIoInfo>) {
+    match disk_io {
+        Some(DiskIoInfo::Success { devices }) => {
+            let header = Row::new(vec![
+                "DEVICE",
+                "MB/s",
+                "READ",
+                "WRITE",
+                "IOPS R/W",
+                "AWAIT R/W",
+                "UTIL",
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+            let optional =
+                |v: Option<f32>| v.map(|v| format!("{v:.1}")).unwrap_or_else(|| "-".into());
+            let rows = devices.iter().map(|d| {
+                let style = if d.util_percent.is_some_and(|u| u >= 80.0) {
+                    Style::default().fg(Color::Red)
+                } else {
+                    Style::default()
+                };
+                Row::new(vec![
+                    d.name.clone(),
+                    format!("{:.1}", d.total_mb_s),
+                    optional(d.read_mb_s),
+                    optional(d.write_mb_s),
+                    match (d.read_iops, d.write_iops) {
+                        (Some(r), Some(w)) => format!("{r:.0}/{w:.0}"),
+                        _ => format!("{:.0}", d.total_iops),
+                    },
+                    match (d.read_await_ms, d.write_await_ms) {
+                        (Some(r), Some(w)) => format!("{r:.1}/{w:.1}"),
+                        _ => "-".into(),
+                    },
+                    d.util_percent
+                        .map(|u| format!("{u:.0}%"))
+                        .unwrap_or_else(|| "-".into()),
+                ])
+                .style(style)
+            });
+            let io_table = Table::new(
+                rows,
+                [
+                    Constraint::Min(8),
+                    Constraint::Length(7),
+                    Constraint::Length(7),
+                    Constraint::Length(7),
+                    Constraint::Length(9),
+                    Constraint::Length(10),
+                    Constraint::Length(5),
+                ],
+            )
+            .header(header)
+            .block(Block::default().borders(Borders::ALL).title("Disk I/O"));
+            frame.render_widget(io_table, area);
+        }
+        other => {
+            let io_lines: Vec<Line> = match other {
+                Some(DiskIoInfo::Failure(e)) => vec![Line::styled(
+                    format!("Error: {e}"),
+                    Style::default().fg(Color::Red),
+                )],
+                Some(DiskIoInfo::Loading) => vec![Line::raw("Loading...")],
+                _ => vec![Line::raw("N/A")],
+            };
+            let io_block = Paragraph::new(io_lines)
+                .block(Block::default().borders(Borders::ALL).title("Disk I/O"));
+            frame.render_widget(io_block, area);
+        }
+    }
+}
+
+fn render_network(frame: &mut Frame, area: Rect, network: Option<&NetworkInfo>) {
+    match network {
+        Some(NetworkInfo::Success { interfaces }) => {
+            let header = Row::new(vec![
+                "IFACE",
+                "LINK",
+                "RX/s",
+                "TX/s",
+                "ERR RX/TX",
+                "DROP RX/TX",
+                "ADDRESSES",
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+            let rows = interfaces.iter().map(|i| {
+                let link = if i.link_up {
+                    Span::styled("up", Style::default().fg(Color::Green))
+                } else {
+                    Span::styled("down", Style::default().fg(Color::Red))
+                };
+                let drops = match (i.rx_drops, i.tx_drops) {
+                    (Some(rx), Some(tx)) => format!("{rx}/{tx}"),
+                    _ => "-".to_string(),
+                };
+                let addresses = i
+                    .ipv4
+                    .iter()
+                    .chain(i.ipv6.iter())
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                Row::new(vec![
+                    Cell::from(i.name.clone()),
+                    Cell::from(link),
+                    Cell::from(format!("{}/s", format_bytes(i.rx_bytes_per_sec as u64))),
+                    Cell::from(format!("{}/s", format_bytes(i.tx_bytes_per_sec as u64))),
+                    Cell::from(format!("{}/{}", i.rx_errors, i.tx_errors)),
+                    Cell::from(drops),
+                    Cell::from(addresses),
+                ])
+            });
+            let net_table = Table::new(
+                rows,
+                [
+                    Constraint::Length(12),
+                    Constraint::Length(5),
+                    Constraint::Length(12),
+                    Constraint::Length(12),
+                    Constraint::Length(10),
+                    Constraint::Length(11),
+                    Constraint::Min(20),
+                ],
+            )
+            .header(header)
+            .block(Block::default().borders(Borders::ALL).title("Network"));
+            frame.render_widget(net_table, area);
+        }
+        other => {
+            let net_lines: Vec<Line> = match other {
+                Some(NetworkInfo::Failure(e)) => vec![Line::styled(
+                    format!("Error: {e}"),
+                    Style::default().fg(Color::Red),
+                )],
+                Some(NetworkInfo::Loading) => vec![Line::raw("Loading...")],
+                _ => vec![Line::raw("N/A")],
+            };
+            let net_block = Paragraph::new(net_lines)
+                .block(Block::default().borders(Borders::ALL).title("Network"));
+            frame.render_widget(net_block, area);
+        }
+    }
+}
+
+fn render_ports(frame: &mut Frame, area: Rect, ports: Option<&PortInfo>) {
+    match ports {
+        Some(PortInfo::Success { ports }) => {
+            let flagged = ports.iter().filter(|p| p.is_flagged()).count();
+            let header = Row::new(vec!["PROTO", "ADDRESS", "PORT", "PROCESS"])
+                .style(Style::default().add_modifier(Modifier::BOLD));
+            let rows = ports.iter().map(|p| {
+                let style = if p.is_flagged() {
+                    Style::default().fg(Color::Red)
+                } else {
+                    Style::default()
+                };
+                let process = match (&p.process, p.pid) {
+                    (Some(name), Some(pid)) => format!("{name} ({pid})"),
+                    (Some(name), None) => name.clone(),
+                    _ => "-".to_string(),
+                };
+                Row::new(vec![
+                    Cell::from(p.protocol.clone()),
+                    Cell::from(p.address.clone()),
+                    Cell::from(p.port.to_string()),
+                    Cell::from(process),
+                ])
+                .style(style)
+            });
+            let (title, title_style) = if flagged > 0 {
+                (
+                    format!("🔌 Listening ports ({flagged} exposed, not allowed)"),
+                    Style::default().fg(Color::Red),
+                )
+            } else {
+                ("🔌 Listening ports".to_string(), Style::default())
+            };
+            let port_table = Table::new(
+                rows,
+                [
+                    Constraint::Length(5),
+                    Constraint::Length(16),
+                    Constraint::Length(6),
+                    Constraint::Min(10),
+                ],
+            )
+            .header(header)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(Span::styled(title, title_style)),
+            );
+            frame.render_widget(port_table, area);
+        }
+        other => {
+            let port_lines: Vec<Line> = match other {
+                Some(PortInfo::Failure(e)) => vec![Line::styled(
+                    format!("Error: {e}"),
+                    Style::default().fg(Color::Red),
+                )],
+                Some(PortInfo::Loading) => vec![Line::raw("Loading...")],
+                _ => vec![Line::raw("N/A")],
+            };
+            let port_block = Paragraph::new(port_lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("🔌 Listening ports"),
+            );
+            frame.render_widget(port_block, area);
+        }
+    }
+}
+
+fn render_sensors(frame: &mut Frame, area: Rect, sensors: Option<&SensorInfo>) {
+    match sensors {
+        Some(SensorInfo::Success { temperatures, fans }) => {
+            let header = Row::new(vec!["SENSOR", "VALUE"])
+                .style(Style::default().add_modifier(Modifier::BOLD));
+            let sensor_table = Table::new(
+                sensor_table_rows(temperatures, fans),
+                [Constraint::Percentage(60), Constraint::Percentage(40)],
+            )
+            .header(header)
+            .block(Block::default().borders(Borders::ALL).title("🌡 Sensors"));
+            frame.render_widget(sensor_table, area);
+        }
+        other => {
+            let sensor_lines: Vec<Line> = match other {
+                Some(SensorInfo::Failure(e)) if e == NO_SENSORS_FOUND => {
+                    vec![Line::raw(NO_SENSORS_FOUND)]
+                }
+                Some(SensorInfo::Failure(e)) => vec![Line::styled(
+                    format!("Error: {e}"),
+                    Style::default().fg(Color::Red),
+                )],
+                Some(SensorInfo::Loading) => vec![Line::raw("Loading...")],
+                _ => vec![Line::raw("N/A")],
+            };
+            let sensor_block = Paragraph::new(sensor_lines)
+                .block(Block::default().borders(Borders::ALL).title("🌡 Sensors"));
+            frame.render_widget(sensor_block, area);
+        }
+    }
+}
+
+fn render_containers(frame: &mut Frame, area: Rect, containers: Option<&ContainerInfo>) {
+    match containers {
+        Some(ContainerInfo::Success {
+            runtime,
+            containers,
+        }) => {
+            let header = Row::new(vec!["NAME", "IMAGE", "STATUS", "CPU", "MEM"])
+                .style(Style::default().add_modifier(Modifier::BOLD));
+            let rows = containers.iter().map(|c| {
+                let status_style = if c.running {
+                    Style::default().fg(Color::Green)
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                };
+                let cpu = c
+                    .cpu_percent
+                    .map(|p| format!("{p:.1}%"))
+                    .unwrap_or_else(|| "-".to_string());
+                let mem = match (c.mem_used_bytes, c.mem_limit_bytes) {
+                    (Some(used), Some(limit)) => {
+                        format!("{} / {}", format_bytes(used), format_bytes(limit))
+                    }
+                    (Some(used), None) => format_bytes(used),
+                    _ => "-".to_string(),
+                };
+                Row::new(vec![
+                    Cell::from(c.name.clone()),
+                    Cell::from(c.image.clone()),
+                    Cell::from(Span::styled(c.status.clone(), status_style)),
+                    Cell::from(cpu),
+                    Cell::from(mem),
+                ])
+            });
+            let container_table = Table::new(
+                rows,
+                [
+                    Constraint::Percentage(22),
+                    Constraint::Percentage(26),
+                    Constraint::Percentage(22),
+                    Constraint::Length(7),
+                    Constraint::Min(12),
+                ],
+            )
+            .header(header)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("🐳 Containers ({runtime})")),
+            );
+            frame.render_widget(container_table, area);
+        }
+        other => {
+            let container_lines: Vec<Line> = match other {
+                Some(ContainerInfo::Failure(e)) if e == NO_CONTAINER_RUNTIME => {
+                    vec![Line::raw(NO_CONTAINER_RUNTIME)]
+                }
+                Some(ContainerInfo::Failure(e)) => vec![Line::styled(
+                    format!("Error: {e}"),
+                    Style::default().fg(Color::Red),
+                )],
+                Some(ContainerInfo::Loading) => vec![Line::raw("Loading...")],
+                _ => vec![Line::raw("N/A")],
+            };
+            let container_block = Paragraph::new(container_lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("🐳 Containers"),
+            );
+            frame.render_widget(container_block, area);
+        }
+    }
+}
+
+fn render_os(frame: &mut Frame, area: Rect, os: Option<&OsInfo>, clock: Option<&ClockInfo>) {
     let os_lines: Vec<Line> = match os {
-        Some(OsInfo::Success {
-            name,
-            version,
-            timezone,
-        }) => vec![
-            Line::raw(format!("{} {}", name, version)),
-            Line::raw(format!("TZ: {}", timezone)),
-        ],
+        Some(OsInfo::Success { details }) => {
+            let machine = match &details.virtualization {
+                Some(virt) => format!("{} ({virt})", details.machine),
+                None => details.machine.to_string(),
+            };
+            vec![
+                Line::raw(format!("{} {}", details.name, details.version)),
+                Line::raw(format!("Host: {}", details.hostname)),
+                Line::raw(format!("Kernel: {} ({})", details.kernel, details.arch)),
+                Line::raw(format!("CPU: {}", details.cpu_model)),
+                Line::raw(format!("Machine: {machine}")),
+                Line::raw(format!("TZ: {}", details.timezone)),
+                clock_line(clock),
+            ]
+        }
         Some(OsInfo::Failure(e)) => vec![Line::styled(
             format!("Error: {e}"),
             Style::default().fg(Color::Red),
@@ -205,71 +904,593 @@ pub fn render(app: &mut App, frame: &mut Frame) {
     };
     let os_block =
         Paragraph::new(os_lines).block(Block::default().borders(Borders::ALL).title("OS Info"));
-    frame.render_widget(os_block, chunks[4]);
+    frame.render_widget(os_block, area);
+}
+
+/// Logged-in users; sessions of users outside the team turn the panel red.
+fn render_sessions(frame: &mut Frame, area: Rect, sessions: Option<&SessionInfo>) {
+    match sessions {
+        Some(SessionInfo::Success { sessions }) => {
+            let outsiders: Vec<&str> = sessions
+                .iter()
+                .filter(|s| s.outsider)
+                .map(|s| s.user.as_str())
+                .collect();
+            let (title, border_style) = if outsiders.is_empty() {
+                (
+                    format!("👥 Sessions ({})", sessions.len()),
+                    Style::default(),
+                )
+            } else {
+                (
+                    format!(
+                        "⚠ Sessions ({}) - non-team: {}",
+                        sessions.len(),
+                        outsiders.join(", ")
+                    ),
+                    Style::default().fg(Color::Red),
+                )
+            };
+            let header = Row::new(vec!["USER", "TTY", "FROM", "LOGIN", "IDLE"])
+                .style(Style::default().add_modifier(Modifier::BOLD));
+            let rows = sessions.iter().map(|s| {
+                let user_style = if s.outsider {
+                    Style::default().fg(Color::Red)
+                } else {
+                    Style::default()
+                };
+                let idle = match s.idle.as_str() {
+                    "." => "active".to_string(),
+                    other => other.to_string(),
+                };
+                Row::new(vec![
+                    Cell::from(Span::styled(s.user.clone(), user_style)),
+                    Cell::from(s.tty.clone()),
+                    Cell::from(s.from.clone().unwrap_or_else(|| "local".to_string())),
+                    Cell::from(s.login_time.clone()),
+                    Cell::from(idle),
+                ])
+            });
+            let session_table = Table::new(
+                rows,
+                [
+                    Constraint::Length(12),
+                    Constraint::Length(10),
+                    Constraint::Min(16),
+                    Constraint::Length(17),
+                    Constraint::Length(7),
+                ],
+            )
+            .header(header)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .border_style(border_style),
+            );
+            frame.render_widget(session_table, area);
+        }
+        other => {
+            let session_lines: Vec<Line> = match other {
+                Some(SessionInfo::Failure(e)) => vec![Line::styled(
+                    format!("Error: {e}"),
+                    Style::default().fg(Color::Red),
+                )],
+                Some(SessionInfo::Loading) => vec![Line::raw("Loading...")],
+                _ => vec![Line::raw("N/A")],
+            };
+            let session_block = Paragraph::new(session_lines)
+                .block(Block::default().borders(Borders::ALL).title("👥 Sessions"));
+            frame.render_widget(session_block, area);
+        }
+    }
+}
+
+fn render_auth_log(frame: &mut Frame, area: Rect, auth_log: Option<&AuthLogInfo>) {
+    match auth_log {
+        Some(AuthLogInfo::Success {
+            source,
+            total_failures,
+            offenders,
+        }) => {
+            let header = Row::new(vec!["SOURCE IP", "FAILS", "USERS", "LAST"])
+                .style(Style::default().add_modifier(Modifier::BOLD));
+            let rows = offenders.iter().map(|o| {
+                let style = if o.brute_force {
+                    Style::default().fg(Color::Red)
+                } else {
+                    Style::default()
+                };
+                Row::new(vec![
+                    Cell::from(o.ip.clone()),
+                    Cell::from(o.attempts.to_string()),
+                    Cell::from(o.users.join(",")),
+                    Cell::from(o.last_seen.clone()),
+                ])
+                .style(style)
+            });
+            let auth_table = Table::new(
+                rows,
+                [
+                    Constraint::Length(16),
+                    Constraint::Length(6),
+                    Constraint::Min(10),
+                    Constraint::Length(19),
+                ],
+            )
+            .header(header)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("🔒 Failed SSH logins: {total_failures} ({source})")),
+            );
+            frame.render_widget(auth_table, area);
+        }
+        other => {
+            let auth_lines: Vec<Line> = match other {
+                Some(AuthLogInfo::Failure(e)) => vec![Line::styled(
+                    format!("Error: {e}"),
+                    Style::default().fg(Color::Red),
+                )],
+                Some(AuthLogInfo::Loading) => vec![Line::raw("Loading...")],
+                _ => vec![Line::raw("N/A")],
+            };
+            let auth_block = Paragraph::new(auth_lines).wrap(Wrap { trim: true }).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("🔒 Failed SSH logins"),
+            );
+            frame.render_widget(auth_block, area);
+        }
+    }
+}
 
-    // Dummy top process tables
+/// The top CPU and memory tables plus the selected process's full command line.
+fn render_processes(app: &mut App, frame: &mut Frame, area: Rect, processes: Option<&ProcessInfo>) {
+    let proc_area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .split(area);
     let proc_chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(chunks[5]);
+        .split(proc_area[0]);
 
-    let cpu_header = Row::new(vec!["PID", "USER", "%CPU", "MEM", "COMMAND"])
-        .style(Style::default().add_modifier(Modifier::BOLD));
-    let cpu_rows = vec![
+    match processes {
+        Some(ProcessInfo::Success { by_cpu, by_memory }) => {
+            let focused_style = |table: ProcessTable| {
+                if app.process_focus == table {
+                    Style::default().fg(Color::Cyan)
+                } else {
+                    Style::default()
+                }
+            };
+
+            let cpu_table = process_table(by_cpu).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("🔝 Top CPU Processes")
+                    .border_style(focused_style(ProcessTable::Cpu)),
+            );
+            frame.render_stateful_widget(cpu_table, proc_chunks[0], &mut app.cpu_process_state);
+
+            let mem_table = process_table(by_memory).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("🧠 Top Memory Processes")
+                    .border_style(focused_style(ProcessTable::Memory)),
+            );
+            frame.render_stateful_widget(mem_table, proc_chunks[1], &mut app.memory_process_state);
+
+            let (list, state) = match app.process_focus {
+                ProcessTable::Cpu => (by_cpu, &app.cpu_process_state),
+                ProcessTable::Memory => (by_memory, &app.memory_process_state),
+            };
+            let command = state
+                .selected()
+                .and_then(|i| list.get(i))
+                .map(|p| format!("[{}] {}", p.pid, p.command))
+                .unwrap_or_default();
+            let command_block = Paragraph::new(command).wrap(Wrap { trim: false }).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Command (Tab: switch table, ↑↓: select, t: TERM, K: KILL, n: renice, e: env, o: files, w: history window)"),
+            );
+            frame.render_widget(command_block, proc_area[1]);
+        }
+        other => {
+            let proc_lines: Vec<Line> = match other {
+                Some(ProcessInfo::Failure(e)) => vec![Line::styled(
+                    format!("Error: {e}"),
+                    Style::default().fg(Color::Red),
+                )],
+                Some(ProcessInfo::Loading) => vec![Line::raw("Loading...")],
+                _ => vec![Line::raw("N/A")],
+            };
+            let proc_block = Paragraph::new(proc_lines)
+                .block(Block::default().borders(Borders::ALL).title("Processes"));
+            frame.render_widget(proc_block, area);
+        }
+    }
+}
+
+fn render_process_dialog(app: &App, dialog: &ProcessDialog, frame: &mut Frame) {
+    let (title, lines, scroll): (String, Vec<Line>, u16) = match dialog {
+        ProcessDialog::Confirm {
+            action,
+            process,
+            use_sudo,
+        } => {
+            let mut lines = vec![
+                Line::raw(format!("{} for PID {}?", action.label(), process.pid)),
+                Line::raw(format!("User: {}", process.user)),
+                Line::raw(format!("Command: {}", process.command)),
+                Line::raw(""),
+                Line::raw(format!(
+                    "Run with sudo -n: {} (s to toggle)",
+                    if *use_sudo { "yes" } else { "no" }
+                )),
+            ];
+            if matches!(action, ProcessAction::Renice(_)) {
+                lines.push(Line::raw("+/-: change niceness"));
+            }
+            lines.push(Line::styled(
+                "y/Enter: confirm | n/Esc: cancel",
+                Style::default().fg(Color::Yellow),
+            ));
+            ("Confirm".to_string(), lines, 0)
+        }
+        ProcessDialog::Output {
+            title,
+            request,
+            scroll,
+        } => {
+            let output = futures::executor::block_on(app.process_action_output.lock());
+            let lines = match output.get(request) {
+                None => vec![Line::raw("Running...")],
+                Some(Ok(text)) => text.lines().map(|l| Line::raw(l.to_string())).collect(),
+                Some(Err(e)) => e
+                    .lines()
+                    .map(|l| Line::styled(l.to_string(), Style::default().fg(Color::Red)))
+                    .collect(),
+            };
+            (format!("{title} (↑↓: scroll, Esc: close)"), lines, *scroll)
+        }
+    };
+
+    let area = centered_rect(frame.area(), 70, 60);
+    let popup = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .scroll((scroll, 0))
+        .block(Block::default().borders(Borders::ALL).title(title));
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
+}
+
+fn centered_rect(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
+/// Failed and watched units, each failed one followed by its last journal lines.
+fn service_lines(services: Option<&ServiceInfo>) -> Vec<Line<'static>> {
+    match services {
+        Some(ServiceInfo::Success { units }) if units.is_empty() => {
+            vec![Line::styled(
+                "No failed units",
+                Style::default().fg(Color::Green),
+            )]
+        }
+        Some(ServiceInfo::Success { units }) => {
+            let mut lines = Vec::new();
+            for unit in units {
+                let color = if unit.is_failed() {
+                    Color::Red
+                } else if unit.is_down() {
+                    Color::Yellow
+                } else {
+                    Color::Green
+                };
+                let mut spans = vec![
+                    Span::styled("● ", Style::default().fg(color)),
+                    Span::raw(format!("{}  ", unit.name)),
+                    Span::styled(
+                        format!(
+                            "{} ({}, {})",
+                            unit.active_state, unit.sub_state, unit.load_state
+                        ),
+                        Style::default().fg(color),
+                    ),
+                ];
+                if unit.watched {
+                    spans.push(Span::styled(
+                        "  [watched]",
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                lines.push(Line::from(spans));
+                lines.extend(unit.journal.iter().map(|l| {
+                    Line::styled(format!("    {l}"), Style::default().fg(Color::DarkGray))
+                }));
+            }
+            lines
+        }
+        Some(ServiceInfo::Failure(e)) if e == NO_SYSTEMD => vec![Line::raw(NO_SYSTEMD)],
+        Some(ServiceInfo::Failure(e)) => vec![Line::styled(
+            format!("Error: {e}"),
+            Style::default().fg(Color::Red),
+        )],
+        Some(ServiceInfo::Loading) => vec![Line::raw("Loading...")],
+        None => vec![Line::raw("N/A")],
+    }
+}
+
+fn clock_line(clock: Option<&ClockInfo>) -> Line<'static> {
+    match clock {
+        Some(ClockInfo::Success { status }) => {
+            let ntp = match status.ntp_synchronized {
+                Some(true) => "NTP synced",
+                Some(false) if status.ntp_enabled == Some(false) => "NTP disabled",
+                Some(false) => "NTP not synced",
+                None => "NTP unknown",
+            };
+            let color = if status.skewed {
+                Color::Red
+            } else if !status.is_healthy() {
+                Color::Yellow
+            } else {
+                Color::White
+            };
+            Line::styled(
+                format!(
+                    "Clock: {:+.3}s (rtt {}ms), {ntp}",
+                    status.skew_secs,
+                    status.round_trip.as_millis()
+                ),
+                Style::default().fg(color),
+            )
+        }
+        Some(ClockInfo::Failure(e)) => {
+            Line::styled(format!("Clock: {e}"), Style::default().fg(Color::Red))
+        }
+        Some(ClockInfo::Loading) => Line::raw("Clock: Loading..."),
+        None => Line::raw("Clock: N/A"),
+    }
+}
+
+fn update_lines(updates: Option<&UpdateInfo>) -> Vec<Line<'static>> {
+    match updates {
+        Some(UpdateInfo::Success { status }) => {
+            let pending = status.pending.len();
+            let pending_color = if pending == 0 {
+                Color::Green
+            } else {
+                Color::Yellow
+            };
+            let mut lines = vec![Line::from(vec![
+                Span::raw(format!("{}: ", status.manager)),
+                Span::styled(
+                    format!("{pending} pending"),
+                    Style::default().fg(pending_color),
+                ),
+            ])];
+            match status.security {
+                Some(0) => lines.push(Line::raw("0 security")),
+                Some(n) => lines.push(Line::styled(
+                    format!("{n} security"),
+                    Style::default().fg(Color::Red),
+                )),
+                None => {}
+            }
+            if let Some(running) = &status.running_kernel {
+                lines.push(Line::raw(format!("Kernel: {running}")));
+            }
+            if status.reboot_required() {
+                lines.push(Line::styled(
+                    "Reboot required",
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                ));
+                lines.extend(
+                    status.reboot_reasons.iter().map(|r| {
+                        Line::styled(format!("  {r}"), Style::default().fg(Color::DarkGray))
+                    }),
+                );
+            }
+            if pending > 0 {
+                lines.push(Line::styled(
+                    status.pending.join(" "),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            lines
+        }
+        Some(UpdateInfo::Failure(e)) if e == NO_PACKAGE_MANAGER => {
+            vec![Line::raw(NO_PACKAGE_MANAGER)]
+        }
+        Some(UpdateInfo::Failure(e)) => vec![Line::styled(
+            format!("Error: {e}"),
+            Style::default().fg(Color::Red),
+        )],
+        Some(UpdateInfo::Loading) => vec![Line::raw("Loading...")],
+        None => vec![Line::raw("N/A")],
+    }
+}
+
+/// One row per package, NVMe and other sensor; per-core readings are folded
+/// into a single max/avg row so many-core machines stay readable.
+fn sensor_table_rows(
+    temperatures: &[TemperatureReading],
+    fans: &[FanReading],
+) -> Vec<Row<'static>> {
+    let temp_row = |name: String, t: &TemperatureReading| {
+        let color = match t.critical_c {
+            Some(crit) if t.celsius >= crit - 10.0 => Color::Red,
+            _ => Color::White,
+        };
+        let value = match t.critical_c {
+            Some(crit) => format!("{:.0}C (crit {:.0}C)", t.celsius, crit),
+            None => format!("{:.0}C", t.celsius),
+        };
         Row::new(vec![
-            "4231",
-            "postgres",
-            "31.2",
-            "512MB",
-            "/usr/lib/postgres",
-        ]),
-        Row::new(vec!["1984", "root", "21.5", "128MB", "/usr/bin/containerd"]),
-    ];
-    let cpu_table = Table::new(
-        cpu_rows,
-        [
-            Constraint::Length(6),
-            Constraint::Length(8),
-            Constraint::Length(6),
-            Constraint::Length(8),
-            Constraint::Min(10),
-        ],
-    )
-    .header(cpu_header)
-    .block(
-        Block::default()
-            .borders(Borders::ALL)
-            .title("🔝 Top CPU Processes"),
+            Cell::from(name),
+            Cell::from(Span::styled(value, Style::default().fg(color))),
+        ])
+    };
+
+    let mut rows: Vec<Row> = temperatures
+        .iter()
+        .filter(|t| t.kind != SensorKind::CpuCore)
+        .map(|t| temp_row(format!("{} {}", t.kind, t.label), t))
+        .collect();
+
+    let cores: Vec<f32> = temperatures
+        .iter()
+        .filter(|t| t.kind == SensorKind::CpuCore)
+        .map(|t| t.celsius)
+        .collect();
+    if !cores.is_empty() {
+        let max = cores.iter().copied().fold(f32::MIN, f32::max);
+        let avg = cores.iter().sum::<f32>() / cores.len() as f32;
+        rows.push(Row::new(vec![
+            format!("CPU cores ({})", cores.len()),
+            format!("max {max:.0}C, avg {avg:.0}C"),
+        ]));
+    }
+
+    rows.extend(
+        fans.iter()
+            .map(|f| Row::new(vec![format!("Fan {}", f.label), format!("{} RPM", f.rpm)])),
     );
-    frame.render_widget(cpu_table, proc_chunks[0]);
+    rows
+}
 
-    let mem_header = Row::new(vec!["PID", "USER", "%MEM", "CPU", "COMMAND"])
-        .style(Style::default().add_modifier(Modifier::BOLD));
-    let mem_rows = vec![
-        Row::new(vec!["1561", "chrome", "19.2", "8.3%", "/opt/chrome/chrome"]),
+fn process_table(processes: &[ProcessEntry]) -> Table<'_> {
+    let header = Row::new(vec![
+        "PID", "USER", "%CPU", "%MEM", "RSS", "TIME", "COMMAND",
+    ])
+    .style(Style::default().add_modifier(Modifier::BOLD));
+    let rows = processes.iter().map(|p| {
         Row::new(vec![
-            "987",
-            "redis",
-            "12.3",
-            "3.1%",
-            "/usr/bin/redis-server",
-        ]),
-    ];
-    let mem_table = Table::new(
-        mem_rows,
+            p.pid.to_string(),
+            p.user.clone(),
+            format!("{:.1}", p.cpu_percent),
+            format!("{:.1}", p.mem_percent),
+            format_bytes(p.rss_kb * 1024),
+            p.elapsed.clone(),
+            p.command.clone(),
+        ])
+    });
+    Table::new(
+        rows,
         [
-            Constraint::Length(6),
-            Constraint::Length(8),
-            Constraint::Length(6),
-            Constraint::Length(6),
+            Constraint::Length(7),
+            Constraint::Length(9),
+            Constraint::Length(5),
+            Constraint::Length(5),
+            Constraint::Length(9),
+            Constraint::Length(11),
             Constraint::Min(10),
         ],
     )
-    .header(mem_header)
-    .block(
-        Block::default()
-            .borders(Borders::ALL)
-            .title("🧠 Top Memory Processes"),
-    );
-    frame.render_widget(mem_table, proc_chunks[1]);
+    .header(header)
+    .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::AppMode;
+    use crate::app::states::{disk::Filesystem, network::NetInterface, os::OsDetails};
+    use ratatui::backend::TestBackend;
+
+    #[test]
+    fn test_process_tables_fit_on_a_45_row_terminal() {
+        let mut app = App::new().unwrap();
+        let id = "web".to_string();
+        app.selected_id = Some(id.clone());
+        app.mode = AppMode::Detail;
+
+        let filesystems = (0..10)
+            .map(|i| Filesystem {
+                device: format!("/dev/sd{i}"),
+                fs_type: "ext4".to_string(),
+                mount_point: format!("/data{i}"),
+                total_bytes: 1 << 40,
+                used_bytes: 1 << 39,
+                avail_bytes: 1 << 39,
+                inodes_total: None,
+                inodes_used: None,
+            })
+            .collect();
+        let interfaces = (0..10)
+            .map(|i| NetInterface {
+                name: format!("eth{i}"),
+                link_up: true,
+                rx_bytes_per_sec: 0.0,
+                tx_bytes_per_sec: 0.0,
+                rx_errors: 0,
+                tx_errors: 0,
+                rx_drops: None,
+                tx_drops: None,
+                ipv4: Vec::new(),
+                ipv6: Vec::new(),
+            })
+            .collect();
+        let process = ProcessEntry {
+            pid: 4242,
+            user: "alice".to_string(),
+            cpu_percent: 97.0,
+            mem_percent: 12.0,
+            rss_kb: 1024,
+            elapsed: "01:00".to_string(),
+            started: None,
+            command: "python train.py".to_string(),
+        };
+        futures::executor::block_on(async {
+            app.disk_info
+                .lock()
+                .await
+                .insert(id.clone(), DiskInfo::success(filesystems));
+            app.network_info
+                .lock()
+                .await
+                .insert(id.clone(), NetworkInfo::success(interfaces));
+            app.os_info
+                .lock()
+                .await
+                .insert(id.clone(), OsInfo::success(OsDetails::default()));
+            app.process_info.lock().await.insert(
+                id.clone(),
+                ProcessInfo::success(vec![process.clone()], vec![process]),
+            );
+        });
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 45)).unwrap();
+        terminal.draw(|frame| render(&mut app, frame)).unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("Top CPU Processes"));
+        assert!(screen.contains("Top Memory Processes"));
+        assert!(screen.contains("python train.py"));
+    }
 }
@@ -0,0 +1,58 @@
+use crate::app::states::{MachineKind, OsInfo};
+
+/// OS inventory field the host list is grouped by. Grouping keeps the
+/// current sort order within each group.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GroupKey {
+    None,
+    Os,
+    Kernel,
+    Arch,
+    Machine,
+}
+
+impl GroupKey {
+    pub fn next(self) -> Self {
+        match self {
+            GroupKey::None => GroupKey::Os,
+            GroupKey::Os => GroupKey::Kernel,
+            GroupKey::Kernel => GroupKey::Arch,
+            GroupKey::Arch => GroupKey::Machine,
+            GroupKey::Machine => GroupKey::None,
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            GroupKey::None => "",
+            GroupKey::Os => "OS",
+            GroupKey::Kernel => "Kernel",
+            GroupKey::Arch => "Arch",
+            GroupKey::Machine => "Machine",
+        }
+    }
+
+    /// Group a host falls into; hosts without OS data share one group.
+    pub fn label(self, os: Option<&OsInfo>) -> String {
+        let Some(OsInfo::Success { details }) = os else {
+            return "Unknown".to_string();
+        };
+        let value = match self {
+            GroupKey::None => String::new(),
+            GroupKey::Os => format!("{} {}", details.name, details.version),
+            GroupKey::Kernel => details.kernel.clone(),
+            GroupKey::Arch => details.arch.clone(),
+            GroupKey::Machine => match (&details.machine, &details.virtualization) {
+                (MachineKind::BareMetal | MachineKind::Unknown, _) | (_, None) => {
+                    details.machine.to_string()
+                }
+                (kind, Some(virt)) => format!("{kind} ({virt})"),
+            },
+        };
+        if value.is_empty() {
+            "Unknown".to_string()
+        } else {
+            value
+        }
+    }
+}
@@ -1,7 +1,11 @@
+pub mod group;
 pub mod render_host_row;
+pub mod sort;
 pub mod table_theme;
 pub mod update;
 pub mod view;
 
+pub use group::GroupKey;
+pub use sort::SortKey;
 pub use update::handle_key;
 pub use view::render;
@@ -1,22 +1,43 @@
 use super::table_theme::TableColors;
-use crate::app::states::{CpuInfo, DiskInfo, GpuInfo, MemoryInfo, OsInfo, SshHostInfo, SshStatus};
+use crate::app::formatting::{format_bytes, format_uptime, sparkline};
+use crate::app::host_snapshot::HostSnapshot;
+use crate::app::states::{
+    AuthLogInfo, ClockInfo, ContainerInfo, CpuInfo, DiskInfo, DiskIoInfo, GpuInfo, HostHistory,
+    LoadInfo, MemoryInfo, Metric, NO_CONTAINER_RUNTIME, NO_GPU_FOUND, NO_PACKAGE_MANAGER,
+    NO_SENSORS_FOUND, NO_SYSTEMD, NetworkInfo, OsInfo, PortInfo, SensorInfo, ServiceInfo,
+    SessionInfo, SshHostInfo, SshStatus, UpdateInfo, unix_now,
+};
 use ratatui::prelude::*;
 use ratatui::text::Span;
 use ratatui::widgets::*;
 
-#[allow(clippy::too_many_arguments)]
+/// Samples shown in the list sparklines; at 30s collection this is 4 minutes.
+const SPARKLINE_SAMPLES: usize = 8;
+
 pub fn render_host_row(
     i: usize,
     info: &SshHostInfo,
-    status: &SshStatus,
-    cpu: Option<&CpuInfo>,
-    disk: Option<&DiskInfo>,
-    memory: Option<&MemoryInfo>,
-    os: Option<&OsInfo>,
-    gpu: Option<&GpuInfo>,
+    host: &HostSnapshot,
+    group: Option<String>,
+    history: Option<&HostHistory>,
     colors: &TableColors,
 ) -> Row<'static> {
-    let bg = if i % 2 == 0 {
+    // Recent samples drawn under the current value of CPU, memory and GPU
+    let trend = |metric: Metric| {
+        history
+            .map(|h| sparkline(&h.get(metric).recent(SPARKLINE_SAMPLES), 100.0))
+            .unwrap_or_default()
+    };
+    // Last recorded value, kept in view while the host is loading or unreachable
+    let last_value = |metric: Metric| match history.and_then(|h| h.get(metric).last()) {
+        Some((_, value)) => Line::styled(
+            format!("{} {value:.0}%", trend(metric)),
+            Style::default().fg(Color::DarkGray),
+        ),
+        None => Line::default(),
+    };
+
+    let bg = if i.is_multiple_of(2) {
         colors.normal_row_color
     } else {
         colors.alt_row_color
@@ -24,112 +45,491 @@ pub fn render_host_row(
 
     let user_at_host = format!("{}@{}:{}", info.user, info.ip, info.port);
 
-    let status_cell = match status {
+    let status_cell = match host.status() {
         SshStatus::Connected => {
             Cell::from(Span::styled("Connected", Style::default().fg(Color::Green)))
         }
         SshStatus::Loading => {
             Cell::from(Span::styled("Loading", Style::default().fg(Color::Yellow)))
         }
-        SshStatus::Failed(_) => Cell::from(Span::styled("Failed", Style::default().fg(Color::Red))),
+        SshStatus::Failed(_) => {
+            let mut lines = vec![Line::styled("Failed", Style::default().fg(Color::Red))];
+            if let Some(at) = history.and_then(HostHistory::last_seen) {
+                lines.push(Line::styled(
+                    format!("seen {} ago", format_uptime(unix_now().saturating_sub(at))),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            Cell::from(Text::from(lines))
+        }
     };
 
-    let cpu_cell = match cpu {
+    let cpu_cell = match &host.cpu {
         Some(CpuInfo::Success {
             core_count,
             usage_percent,
-        }) => Cell::from(Span::styled(
-            format!("{core_count}c, {usage_percent:.0}%"),
+        }) => Cell::from(Text::from(vec![
+            Line::styled(
+                format!("{core_count}c, {usage_percent:.0}%"),
+                Style::default().fg(Color::White),
+            ),
+            Line::styled(trend(Metric::Cpu), Style::default().fg(Color::Cyan)),
+        ])),
+        Some(CpuInfo::Failure(_)) => Cell::from(Text::from(vec![
+            Line::styled("Failed", Style::default().fg(Color::Red)),
+            last_value(Metric::Cpu),
+        ])),
+        Some(CpuInfo::Loading) => Cell::from(Text::from(vec![
+            Line::styled("Loading...", Style::default().fg(Color::Yellow)),
+            last_value(Metric::Cpu),
+        ])),
+        None => Cell::from(Text::from(vec![
+            Line::raw("Unknown"),
+            last_value(Metric::Cpu),
+        ])),
+    };
+
+    let disk_cell = match &host.disk {
+        Some(DiskInfo::Success { filesystems }) => {
+            // Show the fullest filesystem; that is the one that will bite first
+            match filesystems
+                .iter()
+                .max_by(|a, b| a.usage_percent().total_cmp(&b.usage_percent()))
+            {
+                Some(fs) => {
+                    let usage = fs.usage_percent();
+                    let color = if usage >= 90.0 {
+                        Color::Red
+                    } else {
+                        Color::White
+                    };
+                    Cell::from(Span::styled(
+                        format!("{usage:.0}% {}", fs.mount_point),
+                        Style::default().fg(color),
+                    ))
+                }
+                None => Cell::from("N/A"),
+            }
+        }
+        Some(DiskInfo::Failure(_)) => {
+            Cell::from(Span::styled("Failed", Style::default().fg(Color::Red)))
+        }
+        Some(DiskInfo::Loading) => Cell::from(Span::styled(
+            "Loading...",
+            Style::default().fg(Color::Yellow),
+        )),
+        None => Cell::from("Unknown"),
+    };
+
+    let memory_cell = match &host.memory {
+        Some(MemoryInfo::Success { stats }) => {
+            let usage = stats.usage_percent();
+            let color = if usage >= 90.0 {
+                Color::Red
+            } else {
+                Color::White
+            };
+            Cell::from(Text::from(vec![
+                Line::styled(format!("{usage:.1}%"), Style::default().fg(color)),
+                Line::styled(trend(Metric::Memory), Style::default().fg(Color::Magenta)),
+            ]))
+        }
+        Some(MemoryInfo::Failure(_)) => Cell::from(Text::from(vec![
+            Line::styled("Failed", Style::default().fg(Color::Red)),
+            last_value(Metric::Memory),
+        ])),
+        Some(MemoryInfo::Loading) => Cell::from(Text::from(vec![
+            Line::styled("Loading...", Style::default().fg(Color::Yellow)),
+            last_value(Metric::Memory),
+        ])),
+        None => Cell::from(Text::from(vec![
+            Line::raw("Unknown"),
+            last_value(Metric::Memory),
+        ])),
+    };
+
+    let os_cell = match &host.os {
+        Some(OsInfo::Success { details }) => Cell::from(Span::styled(
+            details.name.clone(),
             Style::default().fg(Color::White),
         )),
-        Some(CpuInfo::Failure(_)) => {
+        Some(OsInfo::Failure(_)) => {
             Cell::from(Span::styled("Failed", Style::default().fg(Color::Red)))
         }
-        Some(CpuInfo::Loading) => Cell::from(Span::styled(
+        Some(OsInfo::Loading) => Cell::from(Span::styled(
             "Loading...",
             Style::default().fg(Color::Yellow),
         )),
         None => Cell::from("Unknown"),
     };
 
-    let disk_cell = match disk {
-        Some(DiskInfo::Success { usage_percent, .. }) => Cell::from(Span::styled(
-            format!("{:.1}%", usage_percent),
-            Style::default().fg(Color::White),
+    let gpu_cell = match &host.gpu {
+        Some(GpuInfo::Success { devices }) => {
+            let summary = match devices.as_slice() {
+                [] => "N/A".to_string(),
+                [gpu] => format!("{}C, {}%", gpu.temperature_c, gpu.utilization_percent),
+                _ => {
+                    let max_temp = devices.iter().map(|g| g.temperature_c).max().unwrap_or(0);
+                    let avg_util = devices
+                        .iter()
+                        .map(|g| g.utilization_percent as u32)
+                        .sum::<u32>()
+                        / devices.len() as u32;
+                    format!("{}×, max {max_temp}C, avg {avg_util}%", devices.len())
+                }
+            };
+
+            // Second line of the row lists who currently holds GPU memory
+            let mut owners: Vec<&str> = devices
+                .iter()
+                .flat_map(|g| g.processes.iter().map(|p| p.user.as_str()))
+                .collect();
+            owners.sort_unstable();
+            owners.dedup();
+
+            let second_line = Line::from(vec![
+                Span::styled(trend(Metric::Gpu), Style::default().fg(Color::Green)),
+                Span::raw(" "),
+                Span::styled(owners.join(","), Style::default().fg(Color::Cyan)),
+            ]);
+            Cell::from(Text::from(vec![
+                Line::styled(summary, Style::default().fg(Color::White)),
+                second_line,
+            ]))
+        }
+        Some(GpuInfo::Failure(e)) => {
+            if e == NO_GPU_FOUND {
+                Cell::from("N/A")
+            } else {
+                Cell::from(Span::styled("Failed", Style::default().fg(Color::Red)))
+            }
+        }
+        Some(GpuInfo::Loading) => Cell::from(Span::styled(
+            "Loading...",
+            Style::default().fg(Color::Yellow),
         )),
-        Some(DiskInfo::Failure(_)) => {
+        None => Cell::from("Unknown"),
+    };
+
+    let load_cell = match &host.load {
+        Some(LoadInfo::Success {
+            load_1,
+            uptime_secs,
+            ..
+        }) => {
+            // Normalize by core count so 1.00 means "every core busy"
+            let (text, color) = match &host.cpu {
+                Some(CpuInfo::Success { core_count, .. }) if *core_count > 0 => {
+                    let normalized = load_1 / *core_count as f32;
+                    let color = if normalized >= 1.0 {
+                        Color::Red
+                    } else {
+                        Color::White
+                    };
+                    (format!("{normalized:.2}"), color)
+                }
+                _ => (format!("{load_1:.2}"), Color::White),
+            };
+            Cell::from(Span::styled(
+                format!("{text}, up {}", format_uptime(*uptime_secs)),
+                Style::default().fg(color),
+            ))
+        }
+        Some(LoadInfo::Failure(_)) => {
             Cell::from(Span::styled("Failed", Style::default().fg(Color::Red)))
         }
-        Some(DiskInfo::Loading) => Cell::from(Span::styled(
+        Some(LoadInfo::Loading) => Cell::from(Span::styled(
+            "Loading...",
+            Style::default().fg(Color::Yellow),
+        )),
+        None => Cell::from("Unknown"),
+    };
+
+    let disk_io_cell = match &host.disk_io {
+        Some(io @ DiskIoInfo::Success { devices }) => {
+            let busiest = devices
+                .iter()
+                .filter_map(|d| d.util_percent)
+                .fold(0.0_f32, f32::max);
+            let color = if busiest >= 80.0 {
+                Color::Red
+            } else {
+                Color::White
+            };
+            Cell::from(Span::styled(
+                format!("{:.1} MB/s", io.total_mb_s()),
+                Style::default().fg(color),
+            ))
+        }
+        Some(DiskIoInfo::Failure(_)) => {
+            Cell::from(Span::styled("Failed", Style::default().fg(Color::Red)))
+        }
+        Some(DiskIoInfo::Loading) => Cell::from(Span::styled(
             "Loading...",
             Style::default().fg(Color::Yellow),
         )),
         None => Cell::from("Unknown"),
     };
 
-    let memory_cell = match memory {
-        Some(MemoryInfo::Success { usage_percent, .. }) => Cell::from(Span::styled(
-            usage_percent.clone(),
+    let network_cell = match &host.network {
+        Some(net @ NetworkInfo::Success { .. }) => Cell::from(Span::styled(
+            format!("{}/s", format_bytes(net.total_bytes_per_sec() as u64)),
             Style::default().fg(Color::White),
         )),
-        Some(MemoryInfo::Failure(_)) => {
+        Some(NetworkInfo::Failure(_)) => {
             Cell::from(Span::styled("Failed", Style::default().fg(Color::Red)))
         }
-        Some(MemoryInfo::Loading) => Cell::from(Span::styled(
+        Some(NetworkInfo::Loading) => Cell::from(Span::styled(
             "Loading...",
             Style::default().fg(Color::Yellow),
         )),
         None => Cell::from("Unknown"),
     };
 
-    let os_cell = match os {
-        Some(OsInfo::Success { name, .. }) => Cell::from(Span::styled(
-            name.clone(),
-            Style::default().fg(Color::White),
+    let temp_cell = match &host.sensors {
+        Some(info @ SensorInfo::Success { .. }) => match info.max_temperature() {
+            Some(hottest) => {
+                let critical = hottest.critical_c.unwrap_or(95.0);
+                let color = if hottest.celsius >= critical - 10.0 {
+                    Color::Red
+                } else if hottest.celsius >= critical - 25.0 {
+                    Color::Yellow
+                } else {
+                    Color::White
+                };
+                Cell::from(Span::styled(
+                    format!("{:.0}C {}", hottest.celsius, hottest.kind),
+                    Style::default().fg(color),
+                ))
+            }
+            None => Cell::from("N/A"),
+        },
+        Some(SensorInfo::Failure(e)) => {
+            if e == NO_SENSORS_FOUND {
+                Cell::from("N/A")
+            } else {
+                Cell::from(Span::styled("Failed", Style::default().fg(Color::Red)))
+            }
+        }
+        Some(SensorInfo::Loading) => Cell::from(Span::styled(
+            "Loading...",
+            Style::default().fg(Color::Yellow),
         )),
-        Some(OsInfo::Failure(_)) => {
+        None => Cell::from("Unknown"),
+    };
+
+    let service_cell = match &host.services {
+        Some(ServiceInfo::Success { units }) => {
+            let failed = units.iter().filter(|u| u.is_failed()).count();
+            let down = units.iter().filter(|u| u.is_down()).count();
+            let (text, color) = match (failed, down) {
+                (0, 0) => ("OK".to_string(), Color::Green),
+                (failed, 0) => (format!("{failed} failed"), Color::Red),
+                (failed, down) => (format!("{failed} fail, {down} down"), Color::Red),
+            };
+            Cell::from(Span::styled(text, Style::default().fg(color)))
+        }
+        Some(ServiceInfo::Failure(e)) => {
+            if e == NO_SYSTEMD {
+                Cell::from("N/A")
+            } else {
+                Cell::from(Span::styled("Failed", Style::default().fg(Color::Red)))
+            }
+        }
+        Some(ServiceInfo::Loading) => Cell::from(Span::styled(
+            "Loading...",
+            Style::default().fg(Color::Yellow),
+        )),
+        None => Cell::from("Unknown"),
+    };
+
+    let container_cell = match &host.containers {
+        Some(ContainerInfo::Success { containers, .. }) => {
+            let running = containers.iter().filter(|c| c.running).count();
+            Cell::from(Span::styled(
+                format!("{running}/{}", containers.len()),
+                Style::default().fg(Color::White),
+            ))
+        }
+        Some(ContainerInfo::Failure(e)) => {
+            if e == NO_CONTAINER_RUNTIME {
+                Cell::from("N/A")
+            } else {
+                Cell::from(Span::styled("Failed", Style::default().fg(Color::Red)))
+            }
+        }
+        Some(ContainerInfo::Loading) => Cell::from(Span::styled(
+            "Loading...",
+            Style::default().fg(Color::Yellow),
+        )),
+        None => Cell::from("Unknown"),
+    };
+
+    let session_cell = match &host.sessions {
+        Some(SessionInfo::Success { sessions }) => {
+            let outsiders = sessions.iter().filter(|s| s.outsider).count();
+            if outsiders > 0 {
+                Cell::from(Span::styled(
+                    format!("{} ({outsiders}!)", sessions.len()),
+                    Style::default().fg(Color::Red),
+                ))
+            } else {
+                Cell::from(Span::styled(
+                    sessions.len().to_string(),
+                    Style::default().fg(Color::White),
+                ))
+            }
+        }
+        Some(SessionInfo::Failure(_)) => {
             Cell::from(Span::styled("Failed", Style::default().fg(Color::Red)))
         }
-        Some(OsInfo::Loading) => Cell::from(Span::styled(
+        Some(SessionInfo::Loading) => Cell::from(Span::styled(
             "Loading...",
             Style::default().fg(Color::Yellow),
         )),
         None => Cell::from("Unknown"),
     };
 
-    let gpu_cell = match gpu {
-        Some(GpuInfo::Success {
-            temperature_c,
-            utilization_percent,
+    let auth_cell = match &host.auth_log {
+        Some(AuthLogInfo::Success {
+            total_failures,
+            offenders,
             ..
-        }) => Cell::from(Span::styled(
-            format!("{temperature_c}C, {utilization_percent}%"),
-            Style::default().fg(Color::White),
+        }) => {
+            let brute_force = offenders.iter().filter(|o| o.brute_force).count();
+            let color = if brute_force > 0 {
+                Color::Red
+            } else {
+                Color::White
+            };
+            let text = if brute_force > 0 {
+                format!("{total_failures} ({brute_force} IP!)")
+            } else {
+                total_failures.to_string()
+            };
+            Cell::from(Span::styled(text, Style::default().fg(color)))
+        }
+        Some(AuthLogInfo::Failure(_)) => {
+            Cell::from(Span::styled("Failed", Style::default().fg(Color::Red)))
+        }
+        Some(AuthLogInfo::Loading) => Cell::from(Span::styled(
+            "Loading...",
+            Style::default().fg(Color::Yellow),
         )),
-        Some(GpuInfo::Failure(e)) => {
-            if e == "nvidia-smi not available" {
+        None => Cell::from("Unknown"),
+    };
+
+    let update_cell = match &host.updates {
+        Some(UpdateInfo::Success { status }) => {
+            let pending = status.pending.len();
+            let security = status.security.unwrap_or(0);
+            let mut text = match security {
+                0 => pending.to_string(),
+                n => format!("{pending} ({n} sec)"),
+            };
+            if status.reboot_required() {
+                text.push_str(" ↻");
+            }
+            let color = if security > 0 || status.reboot_required() {
+                Color::Red
+            } else if pending > 0 {
+                Color::Yellow
+            } else {
+                Color::Green
+            };
+            Cell::from(Span::styled(text, Style::default().fg(color)))
+        }
+        Some(UpdateInfo::Failure(e)) => {
+            if e == NO_PACKAGE_MANAGER {
                 Cell::from("N/A")
             } else {
                 Cell::from(Span::styled("Failed", Style::default().fg(Color::Red)))
             }
         }
-        Some(GpuInfo::Loading) => Cell::from(Span::styled(
+        Some(UpdateInfo::Loading) => Cell::from(Span::styled(
+            "Loading...",
+            Style::default().fg(Color::Yellow),
+        )),
+        None => Cell::from("Unknown"),
+    };
+
+    let clock_cell = match &host.clock {
+        Some(ClockInfo::Success { status }) => {
+            let color = if status.skewed {
+                Color::Red
+            } else if status.ntp_synchronized == Some(false) {
+                Color::Yellow
+            } else {
+                Color::Green
+            };
+            let mut text = format!("{:+.2}s", status.skew_secs);
+            if status.ntp_synchronized == Some(false) {
+                text.push_str(" !NTP");
+            }
+            Cell::from(Span::styled(text, Style::default().fg(color)))
+        }
+        Some(ClockInfo::Failure(_)) => {
+            Cell::from(Span::styled("Failed", Style::default().fg(Color::Red)))
+        }
+        Some(ClockInfo::Loading) => Cell::from(Span::styled(
             "Loading...",
             Style::default().fg(Color::Yellow),
         )),
         None => Cell::from("Unknown"),
     };
 
-    Row::new(vec![
+    let port_cell = match &host.ports {
+        Some(PortInfo::Success { ports }) => {
+            let flagged = ports.iter().filter(|p| p.is_flagged()).count();
+            if flagged > 0 {
+                Cell::from(Span::styled(
+                    format!("{} ({flagged}!)", ports.len()),
+                    Style::default().fg(Color::Red),
+                ))
+            } else {
+                Cell::from(Span::styled(
+                    ports.len().to_string(),
+                    Style::default().fg(Color::White),
+                ))
+            }
+        }
+        Some(PortInfo::Failure(_)) => {
+            Cell::from(Span::styled("Failed", Style::default().fg(Color::Red)))
+        }
+        Some(PortInfo::Loading) => Cell::from(Span::styled(
+            "Loading...",
+            Style::default().fg(Color::Yellow),
+        )),
+        None => Cell::from("Unknown"),
+    };
+
+    let mut cells = vec![
         Cell::from(info.name.clone()),
         Cell::from(user_at_host),
         status_cell,
         cpu_cell,
+        load_cell,
         disk_cell,
+        disk_io_cell,
         memory_cell,
+        network_cell,
+        temp_cell,
+        service_cell,
+        container_cell,
+        session_cell,
+        auth_cell,
+        update_cell,
+        clock_cell,
+        port_cell,
         os_cell,
         gpu_cell,
-    ])
-    .style(Style::default().bg(bg))
-    .height(2)
+    ];
+    if let Some(group) = group {
+        let group_style = Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD);
+        cells.insert(0, Cell::from(Span::styled(group, group_style)));
+    }
+
+    Row::new(cells).style(Style::default().bg(bg)).height(2)
 }
@@ -0,0 +1,18 @@
+/// Column the host list is ordered by. Hosts are always ordered by name
+/// first, so ties on a metric keep a stable alphabetical order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortKey {
+    Name,
+    DiskIo,
+    Network,
+}
+
+impl SortKey {
+    pub fn next(self) -> Self {
+        match self {
+            SortKey::Name => SortKey::DiskIo,
+            SortKey::DiskIo => SortKey::Network,
+            SortKey::Network => SortKey::Name,
+        }
+    }
+}
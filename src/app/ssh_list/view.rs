@@ -1,7 +1,10 @@
+use super::group::GroupKey;
 use super::render_host_row::render_host_row;
+use super::sort::SortKey;
 use super::table_theme::TableColors;
 use crate::app::App;
 use crate::app::AppMode;
+use crate::app::host_snapshot::HostSnapshot;
 use crate::app::states::SshStatus;
 use ratatui::prelude::*;
 use ratatui::text::{Line, Span};
@@ -39,27 +42,15 @@ pub fn render(app: &mut App, frame: &mut Frame) {
         frame.render_widget(title, chunks[0]);
     }
 
-    let hosts_guard = futures::executor::block_on(app.ssh_hosts.lock());
-    let status_guard = futures::executor::block_on(app.ssh_statuses.lock());
-    let cpu_guard = futures::executor::block_on(app.cpu_info.lock());
-    let disk_guard = futures::executor::block_on(app.disk_info.lock());
-    let memory_guard = futures::executor::block_on(app.memory_info.lock());
-    let os_guard = futures::executor::block_on(app.os_info.lock());
-    let gpu_guard = futures::executor::block_on(app.gpu_info.lock());
-
-    let hosts = &*hosts_guard;
-    let statuses = &*status_guard;
-    let cpu_info = &*cpu_guard;
-    let disk_info = &*disk_guard;
-    let os_info = &*os_guard;
-    let memory_info = &*memory_guard;
-    let gpu_info = &*gpu_guard;
+    let hosts = futures::executor::block_on(app.ssh_hosts.lock());
+    let snapshots = HostSnapshot::collect(app, hosts.keys());
+    let history_guard = futures::executor::block_on(app.history.lock());
 
     let mut connected = 0;
     let mut loading = 0;
     let mut failed = 0;
 
-    for status in statuses.values() {
+    for status in snapshots.values().filter_map(|s| s.status.as_ref()) {
         match status {
             SshStatus::Connected => connected += 1,
             SshStatus::Loading => loading += 1,
@@ -105,6 +96,30 @@ pub fn render(app: &mut App, frame: &mut Frame) {
         .collect();
 
     host_entries.sort_by_key(|(_, h)| h.name.clone());
+    match app.sort_key {
+        SortKey::Name => {}
+        SortKey::DiskIo => host_entries.sort_by(|(a, _), (b, _)| {
+            let io = |id: &String| {
+                snapshots[id]
+                    .disk_io
+                    .as_ref()
+                    .map_or(0.0, |d| d.total_mb_s())
+            };
+            io(b).total_cmp(&io(a))
+        }),
+        SortKey::Network => host_entries.sort_by(|(a, _), (b, _)| {
+            let net = |id: &String| {
+                snapshots[id]
+                    .network
+                    .as_ref()
+                    .map_or(0.0, |n| n.total_bytes_per_sec())
+            };
+            net(b).total_cmp(&net(a))
+        }),
+    }
+    if app.group_key != GroupKey::None {
+        host_entries.sort_by_cached_key(|(id, _)| app.group_key.label(snapshots[id].os.as_ref()));
+    }
     app.visible_hosts = host_entries.clone();
 
     let visible_rows = grid_area.height.max(1) as usize;
@@ -123,66 +138,110 @@ pub fn render(app: &mut App, frame: &mut Frame) {
         .iter()
         .enumerate()
         .map(|(i, (id, info))| {
-            let status = statuses.get(id).unwrap_or(&SshStatus::Loading);
-            let cpu = cpu_info.get(id);
-            let disk = disk_info.get(id);
-            let memory = memory_info.get(id);
-            let os = os_info.get(id);
-            let gpu = gpu_info.get(id);
-            render_host_row(i, info, status, cpu, disk, memory, os, gpu, &colors)
+            let host = &snapshots[id];
+            // Only the first row of each group carries the label
+            let group = (app.group_key != GroupKey::None).then(|| {
+                let label = app.group_key.label(host.os.as_ref());
+                let index = start_index + i;
+                let previous = index.checked_sub(1).map(|p| {
+                    app.group_key
+                        .label(snapshots[&host_entries[p].0].os.as_ref())
+                });
+                if i == 0 || previous.as_ref() != Some(&label) {
+                    label
+                } else {
+                    String::new()
+                }
+            });
+            render_host_row(i, info, host, group, history_guard.get(id), &colors)
         });
 
-    let header = Row::new(vec![
-        Cell::from("Name"),
+    let sort_label = |label: &'static str, key: SortKey| {
+        if app.sort_key == key {
+            Cell::from(format!("{label} ▼"))
+        } else {
+            Cell::from(label)
+        }
+    };
+
+    let mut header_cells = vec![
+        sort_label("Name", SortKey::Name),
         Cell::from("User@Host:Port"),
         Cell::from("Status"),
         Cell::from("CPU"),
+        Cell::from("Load"),
         Cell::from("Disk"),
+        sort_label("IO", SortKey::DiskIo),
         Cell::from("Mem"),
+        sort_label("Net", SortKey::Network),
+        Cell::from("Temp"),
+        Cell::from("Units"),
+        Cell::from("Ctr"),
+        Cell::from("Users"),
+        Cell::from("SSH Fail"),
+        Cell::from("Updates"),
+        Cell::from("Clock"),
+        Cell::from("Ports"),
         Cell::from("OS"),
         Cell::from("GPU"),
-    ])
-    .style(
+    ];
+    let mut constraints = vec![
+        Constraint::Length(16),
+        Constraint::Length(40),
+        Constraint::Length(16),
+        Constraint::Length(16),
+        Constraint::Length(16),
+        Constraint::Length(16),
+        Constraint::Length(12),
+        Constraint::Length(16),
+        Constraint::Length(12),
+        Constraint::Length(14),
+        Constraint::Length(16),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(12),
+        Constraint::Length(14),
+        Constraint::Length(12),
+        Constraint::Length(10),
+        Constraint::Length(16),
+        Constraint::Min(16),
+    ];
+    if app.group_key != GroupKey::None {
+        header_cells.insert(0, Cell::from(app.group_key.title()));
+        constraints.insert(0, Constraint::Length(20));
+    }
+
+    let header = Row::new(header_cells).style(
         Style::default()
             .fg(colors.header_fg)
             .bg(colors.header_bg)
             .add_modifier(Modifier::BOLD),
     );
 
-    let table = Table::new(
-        rows,
-        [
-            Constraint::Length(16),
-            Constraint::Length(40),
-            Constraint::Length(16),
-            Constraint::Length(16),
-            Constraint::Length(16),
-            Constraint::Length(16),
-            Constraint::Length(16),
-            Constraint::Min(16),
-        ],
-    )
-    .header(header)
-    .block(Block::default().borders(Borders::ALL).title("SSH Hosts"))
-    .row_highlight_style(colors.selected_row_style)
-    .highlight_symbol("▶ ")
-    .highlight_spacing(HighlightSpacing::Always);
+    let table = Table::new(rows, constraints)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title("SSH Hosts"))
+        .row_highlight_style(colors.selected_row_style)
+        .highlight_symbol("▶ ")
+        .highlight_spacing(HighlightSpacing::Always);
 
     frame.render_stateful_widget(table, grid_area, &mut app.table_state);
 
-    let footer = Paragraph::new(vec![Line::from("ESC: Exit | ↑↓: Scroll | /: Search")])
-        .alignment(Alignment::Center)
-        .style(
-            Style::default()
-                .fg(colors.row_fg)
-                .bg(colors.normal_row_color),
-        )
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Controls")
-                .border_style(Style::default().fg(colors.footer_border_color)),
-        );
+    let footer = Paragraph::new(vec![Line::from(
+        "ESC: Exit | ↑↓: Scroll | /: Search | s: Sort | G: Group | g: Free GPUs",
+    )])
+    .alignment(Alignment::Center)
+    .style(
+        Style::default()
+            .fg(colors.row_fg)
+            .bg(colors.normal_row_color),
+    )
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Controls")
+            .border_style(Style::default().fg(colors.footer_border_color)),
+    );
 
     frame.render_widget(footer, chunks[3]);
 }
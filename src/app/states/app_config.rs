@@ -0,0 +1,196 @@
+use eyre::{Result, WrapErr};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Settings read from `~/.config/ssh-monitoring/config.toml`.
+/// Every section is optional; a missing file yields the defaults.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub disk: DiskConfig,
+    pub services: ServiceConfig,
+    pub sessions: SessionConfig,
+    pub auth_log: AuthLogConfig,
+    pub clock: ClockConfig,
+    pub ports: PortConfig,
+    pub history: HistoryConfig,
+    /// Per-host overrides keyed by the `Host` alias from `~/.ssh/config`.
+    pub hosts: HashMap<String, HostConfig>,
+}
+
+impl AppConfig {
+    /// systemd units to watch on `host`: the host's own list if it has one,
+    /// otherwise the global `[services]` list.
+    pub fn watched_units(&self, host: &str) -> &[String] {
+        self.hosts
+            .get(host)
+            .and_then(|h| h.watched_units.as_deref())
+            .unwrap_or(&self.services.watched_units)
+    }
+
+    /// Ports allowed to listen on all interfaces of `host`: the host's own
+    /// list if it has one, otherwise the global `[ports]` list.
+    pub fn allowed_ports(&self, host: &str) -> &[u16] {
+        self.hosts
+            .get(host)
+            .and_then(|h| h.allowed_ports.as_deref())
+            .unwrap_or(&self.ports.allowed_ports)
+    }
+}
+
+pub type SharedAppConfig = Arc<AppConfig>;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DiskConfig {
+    /// Filesystem types that are never listed (pseudo and in-memory filesystems).
+    pub exclude_fs_types: Vec<String>,
+    /// Mount points under these prefixes are skipped (e.g. snap loop mounts).
+    pub exclude_mount_prefixes: Vec<String>,
+}
+
+impl Default for DiskConfig {
+    fn default() -> Self {
+        Self {
+            exclude_fs_types: [
+                "tmpfs", "devtmpfs", "overlay", "squashfs", "devfs", "autofs", "efivarfs", "nullfs",
+            ]
+            .map(String::from)
+            .to_vec(),
+            exclude_mount_prefixes: [
+                "/snap/",
+                "/dev/",
+                "/System/Volumes/VM",
+                "/System/Volumes/Preboot",
+                "/System/Volumes/Update",
+                "/System/Volumes/xarts",
+                "/System/Volumes/iSCPreboot",
+                "/System/Volumes/Hardware",
+            ]
+            .map(String::from)
+            .to_vec(),
+        }
+    }
+}
+
+impl DiskConfig {
+    pub fn is_excluded(&self, fs_type: &str, mount_point: &str) -> bool {
+        self.exclude_fs_types.iter().any(|t| t == fs_type)
+            || self
+                .exclude_mount_prefixes
+                .iter()
+                .any(|p| mount_point.starts_with(p.as_str()))
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ServiceConfig {
+    /// Units reported on every host even when they are not failed.
+    pub watched_units: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// Logins by anyone else are flagged. Empty disables the warning.
+    pub team_users: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct AuthLogConfig {
+    /// Read the journal / auth log through `sudo -n`.
+    pub use_sudo: bool,
+    /// Failed logins older than this are ignored.
+    pub window_hours: u64,
+    /// Source IPs with at least this many failures are flagged as brute force.
+    pub brute_force_threshold: u32,
+}
+
+impl Default for AuthLogConfig {
+    fn default() -> Self {
+        Self {
+            use_sudo: false,
+            window_hours: 24,
+            brute_force_threshold: 20,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ClockConfig {
+    /// Hosts whose clock is off by at least this many seconds are flagged.
+    pub max_skew_secs: f64,
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        Self { max_skew_secs: 1.0 }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PortConfig {
+    /// Ports that may listen on 0.0.0.0 / [::] without being highlighted.
+    pub allowed_ports: Vec<u16>,
+}
+
+impl Default for PortConfig {
+    fn default() -> Self {
+        Self {
+            allowed_ports: vec![22],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// Keep CPU, memory and GPU samples on disk so charts survive restarts.
+    pub persist: bool,
+    /// Samples older than this are dropped from the history file.
+    pub retention_hours: u64,
+    /// Samples older than this are averaged into `downsample_secs` buckets.
+    pub downsample_after_minutes: u64,
+    pub downsample_secs: u64,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            persist: true,
+            retention_hours: 24,
+            downsample_after_minutes: 60,
+            downsample_secs: 300,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HostConfig {
+    pub watched_units: Option<Vec<String>>,
+    pub allowed_ports: Option<Vec<u16>>,
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("ssh-monitoring/config.toml"))
+}
+
+/// Reads the config file. A missing file gives the defaults, but one that
+/// cannot be read or parsed is an error so a typo is not silently ignored.
+pub fn load_app_config() -> Result<AppConfig> {
+    let Some(path) = config_path().filter(|p| p.exists()) else {
+        return Ok(AppConfig::default());
+    };
+
+    let content =
+        fs::read_to_string(&path).wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str(&content).wrap_err_with(|| format!("Invalid config in {}", path.display()))
+}
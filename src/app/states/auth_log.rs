@@ -0,0 +1,268 @@
+use super::app_config::AuthLogConfig;
+use super::platform::{Platform, detect_platform};
+use super::ssh_hosts::SshHostInfo;
+use super::ssh_utils::{connect_ssh_session, run_command};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, LazyLock};
+use tokio::sync::Mutex;
+
+/// Offenders kept per host, ordered by attempt count.
+const TOP_OFFENDERS: usize = 20;
+
+/// One failed attempt per match. Scanners probing unknown users with
+/// publickey or none auth only produce the "Invalid user X from" line, so it
+/// is matched too; a password attempt on that connection then also logs
+/// "Failed ... for invalid user X", which `aggregate_failures` deduplicates.
+static FAILED_LOGIN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"(?:Failed \S+ for (?P<invalid_failed>invalid user )?(?P<user>\S+) from (?P<ip>\S+)",
+        r"|Invalid user (?P<invalid>\S*) from (?P<invalid_ip>\S+)",
+        r"|authentication error for (?:illegal user )?(?P<mac_user>\S+) from (?P<mac_ip>\S+))"
+    ))
+    .expect("valid regex")
+});
+
+/// PID of the sshd process (one per connection) that logged a line.
+static SSHD_PID: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"sshd(?:-session)?\[(\d+)\]").expect("valid regex"));
+
+/// Leading timestamp: ISO (`2024-05-01T10:02:03+0000`) or syslog (`May  1 10:02:03`).
+static TIMESTAMP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{4}-\d{2}-\d{2}[T ][\d:.]+|\w{3}\s+\d+\s+[\d:]+)").expect("valid regex")
+});
+
+#[derive(Debug, Clone)]
+pub struct AuthOffender {
+    pub ip: String,
+    pub attempts: u32,
+    /// Distinct user names tried, in first-seen order.
+    pub users: Vec<String>,
+    pub last_seen: String,
+    /// At or above the configured brute-force threshold.
+    pub brute_force: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum AuthLogInfo {
+    Loading,
+    Success {
+        source: String,
+        total_failures: u32,
+        offenders: Vec<AuthOffender>,
+    },
+    Failure(String),
+}
+
+pub type SharedAuthLogInfo = Arc<Mutex<HashMap<String, AuthLogInfo>>>;
+
+impl AuthLogInfo {
+    pub fn success(source: String, total_failures: u32, offenders: Vec<AuthOffender>) -> Self {
+        AuthLogInfo::Success {
+            source,
+            total_failures,
+            offenders,
+        }
+    }
+
+    pub fn failure(msg: impl Into<String>) -> Self {
+        AuthLogInfo::Failure(msg.into())
+    }
+}
+
+pub fn fetch_auth_log_info(info: &SshHostInfo, config: &AuthLogConfig) -> AuthLogInfo {
+    let session = match connect_ssh_session(info) {
+        Ok(s) => s,
+        Err(e) => return AuthLogInfo::failure(e),
+    };
+
+    let platform = match detect_platform(&session, info) {
+        Ok(p) => p,
+        Err(e) => return AuthLogInfo::failure(e),
+    };
+
+    let sudo = if config.use_sudo { "sudo -n " } else { "" };
+    let hours = config.window_hours;
+
+    let (source, lines): (String, Vec<String>) = match platform {
+        Platform::Linux | Platform::BusyBox | Platform::FreeBsd | Platform::OpenBsd => {
+            let journal = if platform == Platform::Linux {
+                let journal_cmd = format!(
+                    "{sudo}journalctl -u ssh -u sshd --since '{hours}h ago' -o short-iso -q --no-pager 2>/dev/null"
+                );
+                run_command(&session, &journal_cmd).unwrap_or_default()
+            } else {
+                String::new()
+            };
+            if journal.lines().any(|l| l.contains("sshd")) {
+                (
+                    "journal".to_string(),
+                    journal.lines().map(String::from).collect(),
+                )
+            } else {
+                // Log files carry no year and are filtered by day, so the
+                // window is rounded up to whole days here.
+                // BSD date takes an epoch with -r; BusyBox syslogd writes
+                // everything to /var/log/messages.
+                let days = hours.div_ceil(24).max(1);
+                let date_at = if platform.is_linux() { "-d @" } else { "-r " };
+                let log_files = match platform {
+                    Platform::BusyBox => "/var/log/messages",
+                    Platform::OpenBsd => "/var/log/authlog",
+                    _ => "/var/log/auth.log /var/log/secure",
+                };
+                let file_cmd = format!(
+                    "now=$(date +%s); i=0; while [ $i -lt {days} ]; do t=$((now - i * 86400)); \
+                     LC_ALL=C date {date_at}$t '+%b %e'; date {date_at}$t +%F; i=$((i + 1)); done; \
+                     echo ---; {sudo}grep -h sshd {log_files} 2>&1 | tail -n 20000"
+                );
+                let output = match run_command(&session, &file_cmd) {
+                    Ok(out) => out,
+                    Err(e) => return AuthLogInfo::failure(e),
+                };
+                let Some((dates, log)) = output.split_once("---") else {
+                    return AuthLogInfo::failure(format!("Unexpected auth log output: {output}"));
+                };
+                if log.contains("Permission denied") && !log.contains("sshd[") {
+                    return AuthLogInfo::failure(
+                        "Permission denied reading auth log (enable auth_log.use_sudo)",
+                    );
+                }
+                let prefixes: Vec<&str> = dates.lines().filter(|d| !d.is_empty()).collect();
+                let lines = log
+                    .lines()
+                    .filter(|l| prefixes.iter().any(|p| l.starts_with(p)))
+                    .map(String::from)
+                    .collect();
+                ("auth log".to_string(), lines)
+            }
+        }
+        Platform::Darwin => {
+            let log_cmd = format!(
+                "{sudo}log show --style syslog --last {hours}h --predicate 'process == \"sshd\"' 2>/dev/null"
+            );
+            let output = match run_command(&session, &log_cmd) {
+                Ok(out) => out,
+                Err(e) => return AuthLogInfo::failure(e),
+            };
+            (
+                "unified log".to_string(),
+                output.lines().map(String::from).collect(),
+            )
+        }
+    };
+
+    let (total_failures, mut offenders) = aggregate_failures(&lines);
+    offenders.truncate(TOP_OFFENDERS);
+    for offender in &mut offenders {
+        offender.brute_force = offender.attempts >= config.brute_force_threshold;
+    }
+    AuthLogInfo::success(source, total_failures, offenders)
+}
+
+/// Counts failed attempts per source IP, most active first. The first
+/// "Failed ... for invalid user" line of a connection is the attempt its
+/// "Invalid user" line already counted; later ones are further attempts.
+fn aggregate_failures(lines: &[String]) -> (u32, Vec<AuthOffender>) {
+    let mut by_ip: HashMap<String, AuthOffender> = HashMap::new();
+    let mut total = 0;
+    // sshd PIDs whose "Invalid user" line was counted and not yet matched
+    let mut announced: HashSet<String> = HashSet::new();
+
+    for line in lines {
+        let Some(caps) = FAILED_LOGIN.captures(line) else {
+            continue;
+        };
+        let (Some(user), Some(ip)) = (
+            caps.name("user")
+                .or(caps.name("invalid"))
+                .or(caps.name("mac_user")),
+            caps.name("ip")
+                .or(caps.name("invalid_ip"))
+                .or(caps.name("mac_ip")),
+        ) else {
+            continue;
+        };
+
+        let pid = SSHD_PID.captures(line).map(|c| c[1].to_string());
+        if caps.name("invalid").is_some() {
+            announced.extend(pid);
+        } else if caps.name("invalid_failed").is_some()
+            && pid.is_some_and(|pid| announced.remove(&pid))
+        {
+            continue;
+        }
+
+        total += 1;
+        let offender = by_ip
+            .entry(ip.as_str().to_string())
+            .or_insert_with(|| AuthOffender {
+                ip: ip.as_str().to_string(),
+                attempts: 0,
+                users: Vec::new(),
+                last_seen: String::new(),
+                brute_force: false,
+            });
+        offender.attempts += 1;
+        if !offender.users.iter().any(|u| u == user.as_str()) {
+            offender.users.push(user.as_str().to_string());
+        }
+        if let Some(ts) = TIMESTAMP.captures(line) {
+            offender.last_seen = ts[1].to_string();
+        }
+    }
+
+    let mut offenders: Vec<AuthOffender> = by_ip.into_values().collect();
+    offenders.sort_by(|a, b| b.attempts.cmp(&a.attempts).then(a.ip.cmp(&b.ip)));
+    (total, offenders)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aggregate_failures_counts_each_attempt_once() {
+        let log = "\
+2024-05-01T10:00:01+0000 gpu1 sshd[100]: Invalid user admin from 203.0.113.9 port 50122
+2024-05-01T10:00:03+0000 gpu1 sshd[100]: Failed password for invalid user admin from 203.0.113.9 port 50122 ssh2
+2024-05-01T10:00:09+0000 gpu1 sshd[101]: Failed password for root from 203.0.113.9 port 50130 ssh2
+2024-05-01T10:01:00+0000 gpu1 sshd[102]: Failed publickey for alice from 198.51.100.7 port 40000 ssh2
+2024-05-01T10:02:00+0000 gpu1 sshd[103]: Accepted publickey for alice from 198.51.100.7 port 40001 ssh2
+2024-05-01T10:03:00+0000 gpu1 sshd[104]: Failed password for invalid user oracle from 192.0.2.44 port 41000 ssh2
+";
+        let lines: Vec<String> = log.lines().map(String::from).collect();
+        let (total, offenders) = aggregate_failures(&lines);
+
+        assert_eq!(total, 4);
+        assert_eq!(offenders[0].ip, "203.0.113.9");
+        assert_eq!(offenders[0].attempts, 2);
+        assert_eq!(offenders[0].users, vec!["admin", "root"]);
+        assert_eq!(offenders[0].last_seen, "2024-05-01T10:00:09");
+        assert_eq!(offenders[1].ip, "192.0.2.44");
+        assert_eq!(offenders[1].users, vec!["oracle"]);
+        assert_eq!(offenders[2].attempts, 1);
+    }
+
+    #[test]
+    fn test_aggregate_failures_counts_invalid_users_without_failed_lines() {
+        // Publickey scans of unknown users never log a "Failed" line
+        let log = "\
+May  1 10:00:01 gpu1 sshd[200]: Invalid user oracle from 192.0.2.50 port 40100
+May  1 10:00:01 gpu1 sshd[200]: Connection closed by invalid user oracle 192.0.2.50 port 40100 [preauth]
+May  1 10:00:02 gpu1 sshd-session[201]: Invalid user ubuntu from 192.0.2.50 port 40102
+May  1 10:00:02 gpu1 sshd-session[201]: Connection closed by invalid user ubuntu 192.0.2.50 port 40102 [preauth]
+May  1 10:00:05 gpu1 sshd[202]: Invalid user test from 192.0.2.50 port 40104
+May  1 10:00:06 gpu1 sshd[202]: Failed password for invalid user test from 192.0.2.50 port 40104 ssh2
+May  1 10:00:08 gpu1 sshd[202]: Failed password for invalid user test from 192.0.2.50 port 40104 ssh2
+";
+        let lines: Vec<String> = log.lines().map(String::from).collect();
+        let (total, offenders) = aggregate_failures(&lines);
+
+        // Two scans, plus two password tries on the third connection
+        assert_eq!(total, 4);
+        assert_eq!(offenders.len(), 1);
+        assert_eq!(offenders[0].users, vec!["oracle", "ubuntu", "test"]);
+        assert_eq!(offenders[0].last_seen, "May  1 10:00:08");
+    }
+}
@@ -0,0 +1,144 @@
+use super::app_config::ClockConfig;
+use super::ssh_hosts::SshHostInfo;
+use super::ssh_utils::{connect_ssh_session, run_command};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+
+/// Samples taken per check; the one with the shortest round trip is kept.
+const SAMPLES: usize = 3;
+
+#[derive(Debug, Clone)]
+pub struct ClockStatus {
+    /// Remote minus local time, corrected for half the round trip.
+    pub skew_secs: f64,
+    pub round_trip: Duration,
+    /// `None` when the host has no `timedatectl`.
+    pub ntp_synchronized: Option<bool>,
+    pub ntp_enabled: Option<bool>,
+    /// Skew is at or above the configured threshold.
+    pub skewed: bool,
+}
+
+impl ClockStatus {
+    pub fn is_healthy(&self) -> bool {
+        !self.skewed && self.ntp_synchronized != Some(false)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ClockInfo {
+    Loading,
+    Success { status: ClockStatus },
+    Failure(String),
+}
+
+pub type SharedClockInfo = Arc<Mutex<HashMap<String, ClockInfo>>>;
+
+impl ClockInfo {
+    pub fn success(status: ClockStatus) -> Self {
+        ClockInfo::Success { status }
+    }
+
+    pub fn failure(msg: impl Into<String>) -> Self {
+        ClockInfo::Failure(msg.into())
+    }
+}
+
+pub fn fetch_clock_info(info: &SshHostInfo, config: &ClockConfig) -> ClockInfo {
+    let session = match connect_ssh_session(info) {
+        Ok(s) => s,
+        Err(e) => return ClockInfo::failure(e),
+    };
+
+    // macOS `date` has no %N and prints a literal "N"; whole seconds are
+    // still enough to catch real skew there.
+    let date_cmd = "date +%s.%N";
+    let mut best: Option<(f64, Duration)> = None;
+    for _ in 0..SAMPLES {
+        let started = Instant::now();
+        let local_before = unix_now_secs_f64();
+        let output = match run_command(&session, date_cmd) {
+            Ok(out) => out,
+            Err(e) => return ClockInfo::failure(e),
+        };
+        let round_trip = started.elapsed();
+        let Some(remote) = parse_remote_time(&output) else {
+            return ClockInfo::failure(format!("Unexpected date output: {}", output.trim()));
+        };
+        let skew = remote - (local_before + round_trip.as_secs_f64() / 2.0);
+        if best.is_none_or(|(_, rtt)| round_trip < rtt) {
+            best = Some((skew, round_trip));
+        }
+    }
+    let Some((skew_secs, round_trip)) = best else {
+        return ClockInfo::failure("No clock samples");
+    };
+
+    let ntp_cmd = "timedatectl show -p NTPSynchronized -p NTP 2>/dev/null";
+    let (ntp_synchronized, ntp_enabled) = run_command(&session, ntp_cmd)
+        .map(|out| parse_timedatectl(&out))
+        .unwrap_or((None, None));
+
+    ClockInfo::success(ClockStatus {
+        skew_secs,
+        round_trip,
+        ntp_synchronized,
+        ntp_enabled,
+        skewed: skew_secs.abs() >= config.max_skew_secs,
+    })
+}
+
+/// Local wall clock with sub-second precision, to compare against `date`.
+fn unix_now_secs_f64() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or_default()
+}
+
+/// Parses `date +%s.%N`. Without `%N` the seconds are truncated, so the
+/// middle of that second is assumed to halve the worst-case error.
+fn parse_remote_time(output: &str) -> Option<f64> {
+    let output = output.trim();
+    output.parse().ok().or_else(|| {
+        let seconds: f64 = output.split('.').next()?.parse().ok()?;
+        Some(seconds + 0.5)
+    })
+}
+
+/// Parses `timedatectl show -p NTPSynchronized -p NTP` into (synchronized, enabled).
+fn parse_timedatectl(output: &str) -> (Option<bool>, Option<bool>) {
+    let mut synchronized = None;
+    let mut enabled = None;
+    for line in output.lines() {
+        match line.trim().split_once('=') {
+            Some(("NTPSynchronized", v)) => synchronized = Some(v == "yes"),
+            Some(("NTP", v)) => enabled = Some(v == "yes"),
+            _ => {}
+        }
+    }
+    (synchronized, enabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_remote_time_and_timedatectl() {
+        assert_eq!(
+            parse_remote_time("1714557723.250000000\n"),
+            Some(1714557723.25)
+        );
+        assert_eq!(parse_remote_time("1714557723.N\n"), Some(1714557723.5));
+        assert_eq!(parse_remote_time("date: illegal option"), None);
+
+        assert_eq!(
+            parse_timedatectl("NTP=yes\nNTPSynchronized=no\n"),
+            (Some(false), Some(true))
+        );
+        assert_eq!(parse_timedatectl(""), (None, None));
+    }
+}
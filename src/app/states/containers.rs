@@ -0,0 +1,195 @@
+use super::ssh_hosts::SshHostInfo;
+use super::ssh_utils::{connect_ssh_session, run_command_lenient};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub const NO_CONTAINER_RUNTIME: &str = "No container runtime found";
+
+#[derive(Debug, Clone)]
+pub struct Container {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    /// Human readable status including uptime, e.g. "Up 3 hours".
+    pub status: String,
+    pub running: bool,
+    /// Stats are only reported for running containers.
+    pub cpu_percent: Option<f32>,
+    pub mem_used_bytes: Option<u64>,
+    pub mem_limit_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ContainerInfo {
+    Loading,
+    Success {
+        runtime: String,
+        containers: Vec<Container>,
+    },
+    Failure(String),
+}
+
+pub type SharedContainerInfo = Arc<Mutex<HashMap<String, ContainerInfo>>>;
+
+impl ContainerInfo {
+    pub fn success(runtime: String, containers: Vec<Container>) -> Self {
+        ContainerInfo::Success {
+            runtime,
+            containers,
+        }
+    }
+
+    pub fn failure(msg: impl Into<String>) -> Self {
+        ContainerInfo::Failure(msg.into())
+    }
+}
+
+pub fn fetch_container_info(info: &SshHostInfo) -> ContainerInfo {
+    let session = match connect_ssh_session(info) {
+        Ok(s) => s,
+        Err(e) => return ContainerInfo::failure(e),
+    };
+
+    // Works the same on Linux and macOS, so no platform switch is needed.
+    // stderr of `ps` is kept so daemon and permission errors can be shown.
+    let container_cmd = concat!(
+        "if command -v docker >/dev/null 2>&1; then r=docker; ",
+        "elif command -v podman >/dev/null 2>&1; then r=podman; else exit 0; fi; ",
+        "echo $r; $r ps -a --format '{{json .}}' 2>&1; echo ---; ",
+        "$r stats --no-stream --format '{{json .}}' 2>/dev/null"
+    );
+    let output = match run_command_lenient(&session, container_cmd) {
+        Ok(out) => out,
+        Err(e) => return ContainerInfo::failure(e),
+    };
+
+    let mut lines = output.lines();
+    let Some(runtime) = lines.next().map(str::trim).filter(|r| !r.is_empty()) else {
+        return ContainerInfo::failure(NO_CONTAINER_RUNTIME);
+    };
+    let rest = lines.collect::<Vec<_>>().join("\n");
+    let (ps_output, stats_output) = rest.split_once("---").unwrap_or((&rest, ""));
+
+    let mut containers = parse_ps_json(ps_output);
+    if containers.is_empty() && !ps_output.trim().is_empty() {
+        return ContainerInfo::failure(ps_output.trim());
+    }
+    attach_stats(&mut containers, stats_output);
+    containers.sort_by(|a, b| b.running.cmp(&a.running).then(a.name.cmp(&b.name)));
+
+    ContainerInfo::success(runtime.to_string(), containers)
+}
+
+/// Reads a string field that docker and podman name or shape differently.
+fn field(value: &Value, keys: &[&str]) -> String {
+    keys.iter()
+        .find_map(|k| match value.get(k)? {
+            Value::String(s) => Some(s.clone()),
+            // podman reports Names as an array
+            Value::Array(items) => Some(
+                items
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// Parses one `{{json .}}` object per line from `docker ps` / `podman ps`.
+fn parse_ps_json(output: &str) -> Vec<Container> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let value: Value = serde_json::from_str(line.trim()).ok()?;
+            let state = field(&value, &["State"]).to_lowercase();
+            Some(Container {
+                id: field(&value, &["ID", "Id"]),
+                name: field(&value, &["Names", "Name"]),
+                image: field(&value, &["Image"]),
+                status: field(&value, &["Status"]),
+                running: state == "running",
+                cpu_percent: None,
+                mem_used_bytes: None,
+                mem_limit_bytes: None,
+            })
+        })
+        .collect()
+}
+
+/// Merges `stats --no-stream` lines into the containers. IDs may be short or
+/// full length depending on the runtime, so either may prefix the other.
+fn attach_stats(containers: &mut [Container], output: &str) {
+    for line in output.lines() {
+        let Ok(value) = serde_json::from_str::<Value>(line.trim()) else {
+            continue;
+        };
+        let id = field(&value, &["ID", "Id", "Container"]);
+        let name = field(&value, &["Name"]);
+        let Some(container) = containers.iter_mut().find(|c| {
+            (!id.is_empty() && (c.id.starts_with(&id) || id.starts_with(&c.id)))
+                || (!name.is_empty() && c.name == name)
+        }) else {
+            continue;
+        };
+
+        container.cpu_percent = field(&value, &["CPUPerc", "CPU"])
+            .trim_end_matches('%')
+            .parse()
+            .ok();
+        let mem_usage = field(&value, &["MemUsage"]);
+        if let Some((used, limit)) = mem_usage.split_once('/') {
+            container.mem_used_bytes = parse_size(used);
+            container.mem_limit_bytes = parse_size(limit);
+        }
+    }
+}
+
+/// Parses sizes like `12.5MiB`, `1.2GB` or `512kB` as printed by docker stats.
+fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let multiplier: f64 = match unit.trim() {
+        "" | "B" => 1.0,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        "TiB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        "kB" | "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        _ => return None,
+    };
+    Some((number.parse::<f64>().ok()? * multiplier) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_docker_ps_and_stats() {
+        let ps = r#"{"Command":"\"nginx -g…\"","ID":"3f2a1b4c5d6e","Image":"nginx:1.25","Names":"web","State":"running","Status":"Up 3 hours"}
+{"Command":"\"python…\"","ID":"9a8b7c6d5e4f","Image":"trainer:latest","Names":"job-42","State":"exited","Status":"Exited (1) 2 days ago"}
+"#;
+        let stats = r#"{"CPUPerc":"12.50%","ID":"3f2a1b4c5d6e","MemPerc":"0.16%","MemUsage":"12.5MiB / 7.6GiB","Name":"web"}"#;
+
+        let mut containers = parse_ps_json(ps);
+        attach_stats(&mut containers, stats);
+
+        assert_eq!(containers.len(), 2);
+        assert!(containers[0].running);
+        assert_eq!(containers[0].cpu_percent, Some(12.5));
+        assert_eq!(containers[0].mem_used_bytes, Some(13_107_200));
+        assert!(!containers[1].running);
+        assert_eq!(containers[1].cpu_percent, None);
+    }
+}
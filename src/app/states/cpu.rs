@@ -1,3 +1,4 @@
+use super::platform::{Platform, detect_platform};
 use super::ssh_hosts::SshHostInfo;
 use super::ssh_utils::run_command;
 use ssh2::Session;
@@ -82,10 +83,21 @@ pub fn fetch_cpu_info(info: &SshHostInfo) -> CpuInfo {
         return CpuInfo::failure("SSH authentication failed");
     }
 
-    let os_name = run_command(&session, "uname").unwrap_or_default();
-    let is_mac = os_name.trim() == "Darwin";
-    let cpu_core_cmd = if is_mac { "sysctl -n hw.ncpu" } else { "nproc" };
-    let cpu_usage_cmd = "ps -A -o %cpu | awk '{s+=$1} END {print s}'";
+    let platform = match detect_platform(&session, info) {
+        Ok(p) => p,
+        Err(e) => return CpuInfo::failure(e),
+    };
+    let cpu_core_cmd = match platform {
+        Platform::Linux => "nproc",
+        Platform::BusyBox => "nproc 2>/dev/null || grep -c ^processor /proc/cpuinfo",
+        Platform::Darwin | Platform::FreeBsd => "sysctl -n hw.ncpu",
+        Platform::OpenBsd => "sysctl -n hw.ncpuonline",
+    };
+    // BusyBox ps has no %cpu column, so /proc/stat is sampled instead
+    let cpu_usage_cmd = match platform {
+        Platform::BusyBox => "head -n 1 /proc/stat; sleep 1; head -n 1 /proc/stat",
+        _ => "ps -A -o %cpu | awk '{s+=$1} END {print s}'",
+    };
 
     let core_str = match run_command(&session, cpu_core_cmd) {
         Ok(s) => s,
@@ -100,10 +112,41 @@ pub fn fetch_cpu_info(info: &SshHostInfo) -> CpuInfo {
         Ok(n) => n,
         Err(e) => return CpuInfo::failure(format!("Parse core count error: {e}")),
     };
-    let usage_percent = match usage_str.trim().parse::<f32>() {
-        Ok(n) => n,
-        Err(e) => return CpuInfo::failure(format!("Parse usage percent error: {e}")),
+    let usage_percent = if platform == Platform::BusyBox {
+        match parse_proc_stat_usage(&usage_str, core_count) {
+            Some(n) => n,
+            None => return CpuInfo::failure(format!("Unexpected /proc/stat output: {usage_str}")),
+        }
+    } else {
+        match usage_str.trim().parse::<f32>() {
+            Ok(n) => n,
+            Err(e) => return CpuInfo::failure(format!("Parse usage percent error: {e}")),
+        }
     };
 
     CpuInfo::success(core_count, usage_percent)
 }
+
+/// Computes usage from two `cpu user nice system idle iowait ...` lines of
+/// `/proc/stat`, scaled so one busy core is 100% like the `ps` sum.
+fn parse_proc_stat_usage(output: &str, core_count: usize) -> Option<f32> {
+    let samples: Vec<(u64, u64)> = output
+        .lines()
+        .filter(|l| l.starts_with("cpu "))
+        .map(|l| {
+            let ticks: Vec<u64> = l
+                .split_whitespace()
+                .skip(1)
+                .filter_map(|v| v.parse().ok())
+                .collect();
+            let idle = ticks.get(3).copied().unwrap_or(0) + ticks.get(4).copied().unwrap_or(0);
+            (ticks.iter().sum(), idle)
+        })
+        .collect();
+    let [(total0, idle0), (total1, idle1)] = samples[..] else {
+        return None;
+    };
+    let total = total1.checked_sub(total0).filter(|t| *t > 0)?;
+    let busy = total.saturating_sub(idle1.saturating_sub(idle0));
+    Some(busy as f32 / total as f32 * 100.0 * core_count as f32)
+}
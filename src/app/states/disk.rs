@@ -1,31 +1,54 @@
+use super::app_config::DiskConfig;
+use super::platform::{Platform, detect_platform};
 use super::ssh_hosts::SshHostInfo;
-use super::ssh_utils::{connect_ssh_session, run_command};
+use super::ssh_utils::{connect_ssh_session, run_command, run_command_lenient};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+#[derive(Debug, Clone)]
+pub struct Filesystem {
+    pub device: String,
+    pub fs_type: String,
+    pub mount_point: String,
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub avail_bytes: u64,
+    pub inodes_total: Option<u64>,
+    pub inodes_used: Option<u64>,
+}
+
+impl Filesystem {
+    /// Usage as `df` computes it: reserved blocks are excluded from the denominator.
+    pub fn usage_percent(&self) -> f32 {
+        let usable = self.used_bytes + self.avail_bytes;
+        if usable == 0 {
+            0.0
+        } else {
+            self.used_bytes as f32 / usable as f32 * 100.0
+        }
+    }
+
+    pub fn inode_usage_percent(&self) -> Option<f32> {
+        match (self.inodes_total, self.inodes_used) {
+            (Some(total), Some(used)) if total > 0 => Some(used as f32 / total as f32 * 100.0),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum DiskInfo {
     Loading,
-    Success {
-        total: String,
-        used: String,
-        avail: String,
-        usage_percent: String,
-    },
+    Success { filesystems: Vec<Filesystem> },
     Failure(String),
 }
 
 pub type SharedDiskInfo = Arc<Mutex<HashMap<String, DiskInfo>>>;
 
 impl DiskInfo {
-    pub fn success(total: String, used: String, avail: String, usage_percent: String) -> Self {
-        DiskInfo::Success {
-            total,
-            used,
-            avail,
-            usage_percent,
-        }
+    pub fn success(filesystems: Vec<Filesystem>) -> Self {
+        DiskInfo::Success { filesystems }
     }
 
     pub fn failure(msg: impl Into<String>) -> Self {
@@ -33,27 +56,262 @@ impl DiskInfo {
     }
 }
 
-pub fn fetch_disk_info(info: &SshHostInfo) -> DiskInfo {
+pub fn fetch_disk_info(info: &SshHostInfo, config: &DiskConfig) -> DiskInfo {
     let session = match connect_ssh_session(info) {
         Ok(s) => s,
         Err(e) => return DiskInfo::failure(e),
     };
 
-    let disk_cmd = "df -h / | awk 'NR==2 {print $2, $3, $4, $5}'";
-    let output = match run_command(&session, disk_cmd) {
-        Ok(out) => out,
+    let platform = match detect_platform(&session, info) {
+        Ok(p) => p,
         Err(e) => return DiskInfo::failure(e),
     };
 
-    let parts: Vec<&str> = output.split_whitespace().collect();
-    if parts.len() < 4 {
-        return DiskInfo::failure(format!("Unexpected df output: {}", output));
+    let mut filesystems = match platform {
+        Platform::Linux | Platform::BusyBox => {
+            // BusyBox df may be built without -T, so types come from /proc/mounts.
+            // df exits 1 when any mount cannot be read but still lists the rest.
+            let mut filesystems = if platform == Platform::BusyBox {
+                let output = match run_command_lenient(&session, "df -P -k") {
+                    Ok(out) => out,
+                    Err(e) => return DiskInfo::failure(e),
+                };
+                let fs_types = run_command(&session, "cat /proc/mounts")
+                    .map(|out| parse_proc_mounts(&out))
+                    .unwrap_or_default();
+                parse_df_posix(&output, &fs_types)
+            } else {
+                let output = match run_command_lenient(&session, "df -P -k -T") {
+                    Ok(out) => out,
+                    Err(e) => return DiskInfo::failure(e),
+                };
+                parse_df_linux(&output)
+            };
+
+            // Inode counts are best-effort; some filesystems (e.g. btrfs) report 0
+            if let Ok(inode_output) = run_command_lenient(&session, "df -P -i") {
+                let inodes = parse_df_inodes(&inode_output);
+                for fs in &mut filesystems {
+                    if let Some((total, used)) = inodes.get(&fs.mount_point) {
+                        fs.inodes_total = Some(*total);
+                        fs.inodes_used = Some(*used);
+                    }
+                }
+            }
+            filesystems
+        }
+        // FreeBSD and OpenBSD share the macOS df layout
+        Platform::Darwin | Platform::FreeBsd | Platform::OpenBsd => {
+            let df_cmd = "df -k -i";
+            let output = match run_command_lenient(&session, df_cmd) {
+                Ok(out) => out,
+                Err(e) => return DiskInfo::failure(e),
+            };
+            let fs_types = run_command(&session, "mount")
+                .map(|out| parse_mount_types(&out))
+                .unwrap_or_default();
+            parse_df_darwin(&output, &fs_types)
+        }
+    };
+
+    filesystems.retain(|fs| !config.is_excluded(&fs.fs_type, &fs.mount_point));
+    if filesystems.is_empty() {
+        return DiskInfo::failure("No filesystems found in df output");
+    }
+    filesystems.sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+
+    DiskInfo::success(filesystems)
+}
+
+/// Parses `df -P -k -T`:
+/// `Filesystem Type 1024-blocks Used Available Capacity Mounted on`.
+/// The mount point is the remainder of the line so paths with spaces survive.
+fn parse_df_linux(output: &str) -> Vec<Filesystem> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 7 {
+                return None;
+            }
+            Some(Filesystem {
+                device: parts[0].to_string(),
+                fs_type: parts[1].to_string(),
+                mount_point: parts[6..].join(" "),
+                total_bytes: parts[2].parse::<u64>().ok()? * 1024,
+                used_bytes: parts[3].parse::<u64>().ok()? * 1024,
+                avail_bytes: parts[4].parse::<u64>().ok()? * 1024,
+                inodes_total: None,
+                inodes_used: None,
+            })
+        })
+        .collect()
+}
+
+/// Parses `df -P -k` without a type column:
+/// `Filesystem 1024-blocks Used Available Capacity Mounted on`.
+fn parse_df_posix(output: &str, fs_types: &HashMap<String, String>) -> Vec<Filesystem> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 6 {
+                return None;
+            }
+            let mount_point = parts[5..].join(" ");
+            Some(Filesystem {
+                device: parts[0].to_string(),
+                fs_type: fs_types.get(&mount_point).cloned().unwrap_or_default(),
+                total_bytes: parts[1].parse::<u64>().ok()? * 1024,
+                used_bytes: parts[2].parse::<u64>().ok()? * 1024,
+                avail_bytes: parts[3].parse::<u64>().ok()? * 1024,
+                inodes_total: None,
+                inodes_used: None,
+                mount_point,
+            })
+        })
+        .collect()
+}
+
+/// Parses `/proc/mounts` (`device mount_point type options 0 0`) into
+/// `mount point -> filesystem type`. Spaces in paths are escaped as `\040`.
+fn parse_proc_mounts(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let mount_point = parts.nth(1)?.replace("\\040", " ");
+            Some((mount_point, parts.next()?.to_string()))
+        })
+        .collect()
+}
+
+/// Parses `df -P -i` into `mount point -> (inodes, used inodes)`.
+fn parse_df_inodes(output: &str) -> HashMap<String, (u64, u64)> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 6 {
+                return None;
+            }
+            let total = parts[1].parse().ok()?;
+            let used = parts[2].parse().ok()?;
+            Some((parts[5..].join(" "), (total, used)))
+        })
+        .collect()
+}
+
+/// Parses macOS `df -k -i`:
+/// `Filesystem 1024-blocks Used Available Capacity iused ifree %iused Mounted on`.
+fn parse_df_darwin(output: &str, fs_types: &HashMap<String, String>) -> Vec<Filesystem> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 9 {
+                return None;
+            }
+            let mount_point = parts[8..].join(" ");
+            let inodes_used: Option<u64> = parts[5].parse().ok();
+            let inodes_free: Option<u64> = parts[6].parse().ok();
+            Some(Filesystem {
+                device: parts[0].to_string(),
+                fs_type: fs_types.get(&mount_point).cloned().unwrap_or_default(),
+                total_bytes: parts[1].parse::<u64>().ok()? * 1024,
+                used_bytes: parts[2].parse::<u64>().ok()? * 1024,
+                avail_bytes: parts[3].parse::<u64>().ok()? * 1024,
+                inodes_total: inodes_used.zip(inodes_free).map(|(u, f)| u + f),
+                inodes_used,
+                mount_point,
+            })
+        })
+        .collect()
+}
+
+/// Parses BSD `mount` lines like `/dev/disk3s1 on / (apfs, local, journaled)`,
+/// or OpenBSD's `/dev/sd0a on / type ffs (local)`, into
+/// `mount point -> filesystem type`.
+fn parse_mount_types(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (_, rest) = line.split_once(" on ")?;
+            let (mount_point, options) = rest.rsplit_once(" (")?;
+            if let Some((mount_point, fs_type)) = mount_point.rsplit_once(" type ") {
+                return Some((mount_point.to_string(), fs_type.to_string()));
+            }
+            let fs_type = options.split(',').next()?.trim_end_matches(')').trim();
+            Some((mount_point.to_string(), fs_type.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_df_linux_with_inodes_and_spaces() {
+        let df = "\
+Filesystem     Type     1024-blocks      Used Available Capacity Mounted on
+/dev/nvme0n1p2 ext4       959786032 412345678 498602354      46% /
+tmpfs          tmpfs       16384000         0  16384000       0% /dev/shm
+nas:/export    nfs4      2147483648 107374182 2040109466       5% /mnt/team share
+";
+        let inodes = "\
+Filesystem       Inodes   IUsed    IFree IUse% Mounted on
+/dev/nvme0n1p2 61022208 1520345 59501863    3% /
+";
+        let mut filesystems = parse_df_linux(df);
+        let inode_map = parse_df_inodes(inodes);
+        for fs in &mut filesystems {
+            if let Some((total, used)) = inode_map.get(&fs.mount_point) {
+                fs.inodes_total = Some(*total);
+                fs.inodes_used = Some(*used);
+            }
+        }
+        filesystems.retain(|fs| !DiskConfig::default().is_excluded(&fs.fs_type, &fs.mount_point));
+
+        assert_eq!(filesystems.len(), 2);
+        assert_eq!(filesystems[0].total_bytes, 959786032 * 1024);
+        assert_eq!(filesystems[0].inodes_used, Some(1520345));
+        assert_eq!(filesystems[1].mount_point, "/mnt/team share");
+        assert_eq!(filesystems[1].inodes_total, None);
     }
 
-    DiskInfo::success(
-        parts[0].to_string(), // Total
-        parts[1].to_string(), // Used
-        parts[2].to_string(), // Available
-        parts[3].to_string(), // Usage %
-    )
+    #[test]
+    fn test_parse_bsd_df_and_mount_types() {
+        let freebsd_mount = "\
+zroot/ROOT/default on / (zfs, local, noatime, nfsv4acls)
+devfs on /dev (devfs)
+/dev/ada1p1 on /srv/team data (ufs, local, journaled soft-updates)
+";
+        let openbsd_mount =
+            "/dev/sd0a on / type ffs (local)\n/dev/sd0e on /home type ffs (local, nodev, nosuid)\n";
+        let df = "\
+Filesystem         1024-blocks    Used     Avail Capacity iused     ifree %iused  Mounted on
+zroot/ROOT/default    94345148 2345678  91999470     2%  123456 183998940    0%  /
+devfs                        1       1         0   100%       0         0  100%  /dev
+/dev/ada1p1           20307196 8000000  10682624    43%   50000   2600000    2%  /srv/team data
+";
+        let fs_types = parse_mount_types(freebsd_mount);
+        assert_eq!(fs_types["/srv/team data"], "ufs");
+        let openbsd_types = parse_mount_types(openbsd_mount);
+        assert_eq!(openbsd_types["/"], "ffs");
+        assert_eq!(openbsd_types["/home"], "ffs");
+
+        let mut filesystems = parse_df_darwin(df, &fs_types);
+        filesystems.retain(|fs| !DiskConfig::default().is_excluded(&fs.fs_type, &fs.mount_point));
+
+        assert_eq!(filesystems.len(), 2);
+        assert_eq!(filesystems[0].fs_type, "zfs");
+        assert_eq!(filesystems[0].inodes_total, Some(123456 + 183998940));
+        assert_eq!(filesystems[1].mount_point, "/srv/team data");
+        assert_eq!(filesystems[1].used_bytes, 8000000 * 1024);
+    }
 }
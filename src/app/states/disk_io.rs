@@ -0,0 +1,362 @@
+use super::platform::{Platform, detect_platform};
+use super::ssh_hosts::SshHostInfo;
+use super::ssh_utils::{connect_ssh_session, run_command};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+const SECTOR_BYTES: f64 = 512.0;
+const MB: f64 = 1024.0 * 1024.0;
+
+/// Throughput of one block device over the sampling interval.
+/// `iostat` on macOS does not split reads from writes, so those are optional.
+/// It reports no service times either, leaving the await columns empty there.
+#[derive(Debug, Clone)]
+pub struct BlockDeviceIo {
+    pub name: String,
+    pub read_mb_s: Option<f32>,
+    pub write_mb_s: Option<f32>,
+    pub total_mb_s: f32,
+    pub read_iops: Option<f32>,
+    pub write_iops: Option<f32>,
+    pub total_iops: f32,
+    pub util_percent: Option<f32>,
+    /// Average time a read or write took over the interval, queueing included.
+    pub read_await_ms: Option<f32>,
+    pub write_await_ms: Option<f32>,
+}
+
+#[derive(Debug, Clone)]
+pub enum DiskIoInfo {
+    Loading,
+    Success { devices: Vec<BlockDeviceIo> },
+    Failure(String),
+}
+
+pub type SharedDiskIoInfo = Arc<Mutex<HashMap<String, DiskIoInfo>>>;
+
+impl DiskIoInfo {
+    pub fn success(devices: Vec<BlockDeviceIo>) -> Self {
+        DiskIoInfo::Success { devices }
+    }
+
+    pub fn failure(msg: impl Into<String>) -> Self {
+        DiskIoInfo::Failure(msg.into())
+    }
+
+    /// Combined throughput across devices, used for sorting the host list.
+    pub fn total_mb_s(&self) -> f32 {
+        match self {
+            DiskIoInfo::Success { devices } => devices.iter().map(|d| d.total_mb_s).sum(),
+            _ => 0.0,
+        }
+    }
+}
+
+pub fn fetch_disk_io_info(info: &SshHostInfo) -> DiskIoInfo {
+    let session = match connect_ssh_session(info) {
+        Ok(s) => s,
+        Err(e) => return DiskIoInfo::failure(e),
+    };
+
+    let platform = match detect_platform(&session, info) {
+        Ok(p) => p,
+        Err(e) => return DiskIoInfo::failure(e),
+    };
+
+    match platform {
+        Platform::Linux | Platform::BusyBox => {
+            // /sys/block lists whole disks only, which filters out partitions.
+            // /proc/uptime timestamps each sample so the interval is exact.
+            let io_cmd = concat!(
+                "ls /sys/block; echo ---; cat /proc/uptime /proc/diskstats; ",
+                "sleep 1; echo ---; cat /proc/uptime /proc/diskstats"
+            );
+            let output = match run_command(&session, io_cmd) {
+                Ok(out) => out,
+                Err(e) => return DiskIoInfo::failure(e),
+            };
+            match parse_diskstats_samples(&output) {
+                Some(devices) => DiskIoInfo::success(devices),
+                None => DiskIoInfo::failure(format!("Unexpected /proc/diskstats output: {output}")),
+            }
+        }
+        Platform::FreeBsd => {
+            let io_cmd = "iostat -x -c 2 -w 1";
+            let output = match run_command(&session, io_cmd) {
+                Ok(out) => out,
+                Err(e) => return DiskIoInfo::failure(e),
+            };
+            match parse_iostat_extended(&output) {
+                Some(devices) => DiskIoInfo::success(devices),
+                None => DiskIoInfo::failure(format!("Unexpected iostat output: {output}")),
+            }
+        }
+        // OpenBSD `iostat -d` has the same layout, with t/s in place of tps
+        Platform::Darwin | Platform::OpenBsd => {
+            let io_cmd = "iostat -d -c 2 -w 1";
+            let output = match run_command(&session, io_cmd) {
+                Ok(out) => out,
+                Err(e) => return DiskIoInfo::failure(e),
+            };
+            match parse_iostat_darwin(&output) {
+                Some(devices) => DiskIoInfo::success(devices),
+                None => DiskIoInfo::failure(format!("Unexpected iostat output: {output}")),
+            }
+        }
+    }
+}
+
+/// Cumulative counters from one `/proc/diskstats` line.
+struct DiskStat {
+    reads: u64,
+    sectors_read: u64,
+    read_ms: u64,
+    writes: u64,
+    sectors_written: u64,
+    write_ms: u64,
+    io_ms: u64,
+}
+
+fn parse_diskstats(section: &str) -> Option<(f64, HashMap<String, DiskStat>)> {
+    let mut lines = section.lines().filter(|l| !l.trim().is_empty());
+    let uptime = lines.next()?.split_whitespace().next()?.parse().ok()?;
+    let stats = lines
+        .filter_map(|line| {
+            let f: Vec<&str> = line.split_whitespace().collect();
+            if f.len() < 13 {
+                return None;
+            }
+            Some((
+                f[2].to_string(),
+                DiskStat {
+                    reads: f[3].parse().ok()?,
+                    sectors_read: f[5].parse().ok()?,
+                    read_ms: f[6].parse().ok()?,
+                    writes: f[7].parse().ok()?,
+                    sectors_written: f[9].parse().ok()?,
+                    write_ms: f[10].parse().ok()?,
+                    io_ms: f[12].parse().ok()?,
+                },
+            ))
+        })
+        .collect();
+    Some((uptime, stats))
+}
+
+/// Parses the output of the Linux probe: the `/sys/block` listing followed by
+/// two timestamped `/proc/diskstats` snapshots separated by `---`.
+fn parse_diskstats_samples(output: &str) -> Option<Vec<BlockDeviceIo>> {
+    let mut sections = output.split("---");
+    let block_devices: HashSet<&str> = sections.next()?.split_whitespace().collect();
+    let (t0, before) = parse_diskstats(sections.next()?)?;
+    let (t1, after) = parse_diskstats(sections.next()?)?;
+
+    let elapsed = t1 - t0;
+    if elapsed <= 0.0 {
+        return None;
+    }
+
+    let mut devices: Vec<BlockDeviceIo> = after
+        .iter()
+        .filter(|(name, _)| block_devices.contains(name.as_str()))
+        .filter(|(name, _)| !name.starts_with("loop") && !name.starts_with("ram"))
+        .filter_map(|(name, now)| {
+            let prev = before.get(name)?;
+            let rate = |a: u64, b: u64| a.saturating_sub(b) as f64 / elapsed;
+            let read_mb_s = rate(now.sectors_read, prev.sectors_read) * SECTOR_BYTES / MB;
+            let write_mb_s = rate(now.sectors_written, prev.sectors_written) * SECTOR_BYTES / MB;
+            let read_iops = rate(now.reads, prev.reads);
+            let write_iops = rate(now.writes, prev.writes);
+            let util = rate(now.io_ms, prev.io_ms) / 1000.0 * 100.0;
+            // Time spent per completed request; 0 when the device was idle
+            let await_ms = |ms: u64, prev_ms: u64, ops: u64, prev_ops: u64| {
+                let ops = ops.saturating_sub(prev_ops);
+                if ops == 0 {
+                    0.0
+                } else {
+                    ms.saturating_sub(prev_ms) as f32 / ops as f32
+                }
+            };
+            Some(BlockDeviceIo {
+                name: name.clone(),
+                read_mb_s: Some(read_mb_s as f32),
+                write_mb_s: Some(write_mb_s as f32),
+                total_mb_s: (read_mb_s + write_mb_s) as f32,
+                read_iops: Some(read_iops as f32),
+                write_iops: Some(write_iops as f32),
+                total_iops: (read_iops + write_iops) as f32,
+                util_percent: Some(util.min(100.0) as f32),
+                read_await_ms: Some(await_ms(now.read_ms, prev.read_ms, now.reads, prev.reads)),
+                write_await_ms: Some(await_ms(
+                    now.write_ms,
+                    prev.write_ms,
+                    now.writes,
+                    prev.writes,
+                )),
+            })
+        })
+        .collect();
+
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    Some(devices)
+}
+
+/// Parses FreeBSD `iostat -x -c 2 -w 1`. Each report starts with a
+/// `device r/s w/s kr/s kw/s ms/r ms/w ... %b` header; only the last (1s)
+/// report is used.
+fn parse_iostat_extended(output: &str) -> Option<Vec<BlockDeviceIo>> {
+    let lines: Vec<&str> = output.lines().collect();
+    let header_at = lines.iter().rposition(|l| l.starts_with("device"))?;
+    let header: Vec<&str> = lines[header_at].split_whitespace().collect();
+    let column = |name: &str| header.iter().position(|h| *h == name);
+    let (rps, wps, krps, kwps) = (
+        column("r/s")?,
+        column("w/s")?,
+        column("kr/s")?,
+        column("kw/s")?,
+    );
+    let busy = column("%b");
+    let (read_ms, write_ms) = (column("ms/r"), column("ms/w"));
+
+    let mut devices: Vec<BlockDeviceIo> = lines[header_at + 1..]
+        .iter()
+        .map(|l| l.split_whitespace().collect::<Vec<&str>>())
+        .filter(|f| f.len() == header.len() && !f[0].starts_with("cd"))
+        .map(|f| {
+            let value = |i: usize| f[i].parse::<f32>().unwrap_or(0.0);
+            let read_mb_s = value(krps) * 1024.0 / MB as f32;
+            let write_mb_s = value(kwps) * 1024.0 / MB as f32;
+            BlockDeviceIo {
+                name: f[0].to_string(),
+                read_mb_s: Some(read_mb_s),
+                write_mb_s: Some(write_mb_s),
+                total_mb_s: read_mb_s + write_mb_s,
+                read_iops: Some(value(rps)),
+                write_iops: Some(value(wps)),
+                total_iops: value(rps) + value(wps),
+                util_percent: busy.map(value),
+                read_await_ms: read_ms.map(value),
+                write_await_ms: write_ms.map(value),
+            }
+        })
+        .collect();
+
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    Some(devices)
+}
+
+/// Parses `iostat -d -c 2 -w 1` on macOS. The first data row is the
+/// since-boot average, so only the last row (the 1s interval) is used.
+fn parse_iostat_darwin(output: &str) -> Option<Vec<BlockDeviceIo>> {
+    let mut lines = output.lines().filter(|l| !l.trim().is_empty());
+    let names: Vec<&str> = lines.next()?.split_whitespace().collect();
+    let last = lines.next_back()?;
+    let values: Vec<f32> = last
+        .split_whitespace()
+        .filter_map(|v| v.parse().ok())
+        .collect();
+
+    // Three columns per disk: KB/t, tps, MB/s
+    if values.len() < names.len() * 3 {
+        return None;
+    }
+
+    Some(
+        names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| BlockDeviceIo {
+                name: name.to_string(),
+                read_mb_s: None,
+                write_mb_s: None,
+                total_mb_s: values[i * 3 + 2],
+                read_iops: None,
+                write_iops: None,
+                total_iops: values[i * 3 + 1],
+                util_percent: None,
+                read_await_ms: None,
+                write_await_ms: None,
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_diskstats_samples() {
+        let output = "\
+nvme0n1
+loop0
+---
+1000.00 8000.00
+ 259       0 nvme0n1 100 0 2048 50 200 0 4096 80 0 300 130 0 0 0 0
+ 259       1 nvme0n1p1 90 0 2000 40 180 0 4000 70 0 280 110 0 0 0 0
+   7       0 loop0 5 0 10 1 0 0 0 0 0 1 1 0 0 0 0
+---
+1002.00 8016.00
+ 259       0 nvme0n1 300 0 6144 90 600 0 12288 160 0 1300 250 0 0 0 0
+ 259       1 nvme0n1p1 290 0 6000 80 580 0 12000 150 0 1280 230 0 0 0 0
+   7       0 loop0 5 0 10 1 0 0 0 0 0 1 1 0 0 0 0
+";
+        let devices = parse_diskstats_samples(output).expect("samples should parse");
+
+        assert_eq!(devices.len(), 1);
+        let nvme = &devices[0];
+        assert_eq!(nvme.name, "nvme0n1");
+        assert_eq!(nvme.read_iops, Some(100.0));
+        assert_eq!(nvme.write_iops, Some(200.0));
+        // 4096 sectors over 2s = 1 MiB/s
+        assert_eq!(nvme.read_mb_s, Some(1.0));
+        assert_eq!(nvme.util_percent, Some(50.0));
+        // 40ms over 200 reads, 80ms over 400 writes
+        assert_eq!(nvme.read_await_ms, Some(0.2));
+        assert_eq!(nvme.write_await_ms, Some(0.2));
+    }
+
+    #[test]
+    fn test_parse_iostat_extended_freebsd() {
+        let output = "\
+                        extended device statistics
+device       r/s     w/s     kr/s     kw/s  ms/r  ms/w  ms/o  ms/t qlen  %b
+ada0           1       5     12.3     80.1     0     1     0     1    0   0
+cd0            0       0      0.0      0.0     0     0     0     0    0   0
+                        extended device statistics
+device       r/s     w/s     kr/s     kw/s  ms/r  ms/w  ms/o  ms/t qlen  %b
+ada0          20      40   2048.0   4096.0     2     5     0     4    1  37
+cd0            0       0      0.0      0.0     0     0     0     0    0   0
+";
+        let devices = parse_iostat_extended(output).expect("iostat should parse");
+
+        // Only the 1s report, without the optical drive
+        assert_eq!(devices.len(), 1);
+        let ada0 = &devices[0];
+        assert_eq!(ada0.read_mb_s, Some(2.0));
+        assert_eq!(ada0.write_mb_s, Some(4.0));
+        assert_eq!(ada0.total_iops, 60.0);
+        assert_eq!(ada0.util_percent, Some(37.0));
+        assert_eq!(ada0.read_await_ms, Some(2.0));
+        assert_eq!(ada0.write_await_ms, Some(5.0));
+    }
+
+    #[test]
+    fn test_parse_iostat_darwin_layout_on_openbsd() {
+        let output = "\
+               sd0               sd1
+  KB/t  t/s  MB/s  KB/t  t/s  MB/s
+ 25.13    4  0.09  0.00    0  0.00
+ 16.00   12  0.19 64.00    3  0.19
+";
+        let devices = parse_iostat_darwin(output).expect("iostat should parse");
+
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].name, "sd0");
+        assert_eq!(devices[0].total_iops, 12.0);
+        assert_eq!(devices[0].total_mb_s, 0.19);
+        assert_eq!(devices[1].total_iops, 3.0);
+        assert_eq!(devices[1].read_mb_s, None);
+    }
+}
@@ -1,42 +1,147 @@
+use super::platform::{Platform, detect_platform};
 use super::ssh_hosts::SshHostInfo;
-use super::ssh_utils::{connect_ssh_session, run_command};
+use super::ssh_utils::{connect_ssh_session, run_command, run_command_lenient};
+use regex::Regex;
+use serde_json::Value;
+use ssh2::Session;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::fmt;
+use std::sync::{Arc, LazyLock};
+use std::time::Instant;
 use tokio::sync::Mutex;
 
+/// Reported when a Linux host has no GPU that any of the probes recognise.
+pub const NO_GPU_FOUND: &str = "No supported GPU found";
+
+/// A GPU at or below this utilization with no compute processes counts as idle.
+pub const GPU_IDLE_UTIL_PERCENT: u8 = 5;
+
+/// Render engine busy percentage in an `intel_gpu_top -J` sample.
+static INTEL_RENDER_BUSY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#""Render/3D[^"]*"\s*:\s*\{\s*"busy"\s*:\s*([0-9.]+)"#).expect("valid regex")
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuVendor {
+    Nvidia,
+    Amd,
+    Intel,
+    Apple,
+    Unknown,
+}
+
+impl GpuVendor {
+    /// Maps a PCI vendor id as found in `/sys/class/drm/*/device/vendor`.
+    fn from_pci_id(id: &str) -> Self {
+        match id.trim().to_ascii_lowercase().as_str() {
+            "0x10de" => GpuVendor::Nvidia,
+            "0x1002" => GpuVendor::Amd,
+            "0x8086" => GpuVendor::Intel,
+            _ => GpuVendor::Unknown,
+        }
+    }
+
+    /// Best-effort guess from a marketing name such as "Apple M2 Pro".
+    fn from_name(name: &str) -> Self {
+        let name = name.to_ascii_lowercase();
+        if name.contains("nvidia") || name.contains("geforce") {
+            GpuVendor::Nvidia
+        } else if name.contains("amd") || name.contains("radeon") {
+            GpuVendor::Amd
+        } else if name.contains("intel") {
+            GpuVendor::Intel
+        } else if name.contains("apple") {
+            GpuVendor::Apple
+        } else {
+            GpuVendor::Unknown
+        }
+    }
+}
+
+impl fmt::Display for GpuVendor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            GpuVendor::Nvidia => "NVIDIA",
+            GpuVendor::Amd => "AMD",
+            GpuVendor::Intel => "Intel",
+            GpuVendor::Apple => "Apple",
+            GpuVendor::Unknown => "Unknown",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A compute process holding memory on a GPU.
+#[derive(Debug, Clone)]
+pub struct GpuProcess {
+    pub pid: u32,
+    pub user: String,
+    pub used_memory_mb: u32,
+    pub command: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct GpuDevice {
+    pub vendor: GpuVendor,
+    pub index: u32,
+    pub uuid: String,
+    pub name: String,
+    pub memory_total_mb: u32,
+    pub memory_used_mb: u32,
+    pub utilization_percent: u8,
+    pub temperature_c: u8,
+    pub power_draw_w: Option<f32>,
+    pub fan_percent: Option<u8>,
+    pub processes: Vec<GpuProcess>,
+    /// When this GPU was first seen idle in the current idle streak.
+    pub idle_since: Option<Instant>,
+}
+
+impl GpuDevice {
+    pub fn memory_free_mb(&self) -> u32 {
+        self.memory_total_mb.saturating_sub(self.memory_used_mb)
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.utilization_percent <= GPU_IDLE_UTIL_PERCENT && self.processes.is_empty()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum GpuInfo {
     Loading,
-    Success {
-        name: String,
-        memory_total_mb: u32,
-        memory_used_mb: u32,
-        utilization_percent: u8,
-        temperature_c: u8,
-    },
+    Success { devices: Vec<GpuDevice> },
     Failure(String),
 }
 
 impl GpuInfo {
-    pub fn success(
-        name: String,
-        memory_total_mb: u32,
-        memory_used_mb: u32,
-        utilization_percent: u8,
-        temperature_c: u8,
-    ) -> Self {
-        GpuInfo::Success {
-            name,
-            memory_total_mb,
-            memory_used_mb,
-            utilization_percent,
-            temperature_c,
-        }
+    pub fn success(devices: Vec<GpuDevice>) -> Self {
+        GpuInfo::Success { devices }
     }
 
     pub fn failure(msg: impl Into<String>) -> Self {
         GpuInfo::Failure(msg.into())
     }
+
+    /// Stamps `idle_since` on idle devices, keeping the timestamp from the
+    /// previous sample of the same device so idle streaks span refreshes.
+    pub fn track_idle(&mut self, previous: Option<&GpuInfo>, now: Instant) {
+        let GpuInfo::Success { devices } = self else {
+            return;
+        };
+        let previous_devices = match previous {
+            Some(GpuInfo::Success { devices }) => devices.as_slice(),
+            _ => &[],
+        };
+
+        for device in devices.iter_mut().filter(|d| d.is_idle()) {
+            let carried = previous_devices
+                .iter()
+                .find(|p| p.index == device.index && p.uuid == device.uuid)
+                .and_then(|p| p.idle_since);
+            device.idle_since = Some(carried.unwrap_or(now));
+        }
+    }
 }
 
 pub type SharedGpuInfo = Arc<Mutex<HashMap<String, GpuInfo>>>;
@@ -47,47 +152,87 @@ pub fn fetch_gpu_info(info: &SshHostInfo) -> GpuInfo {
         Err(e) => return GpuInfo::failure(e),
     };
 
-    let uname_cmd = "uname -s";
-    let platform = match run_command(&session, uname_cmd) {
-        Ok(out) => out.trim().to_string(),
-        Err(e) => return GpuInfo::failure(format!("Failed to detect platform: {}", e)),
+    let platform = match detect_platform(&session, info) {
+        Ok(p) => p,
+        Err(e) => return GpuInfo::failure(e),
     };
 
-    match platform.as_str() {
-        "Linux" => {
+    match platform {
+        Platform::Linux | Platform::BusyBox => {
             let nvidia_cmd = concat!(
-                "nvidia-smi --query-gpu=name,memory.total,memory.used,utilization.gpu,temperature.gpu ",
+                "nvidia-smi --query-gpu=index,uuid,name,memory.total,memory.used,",
+                "utilization.gpu,temperature.gpu,power.draw,fan.speed ",
                 "--format=csv,noheader,nounits"
             );
 
             if let Ok(out) = run_command(&session, nvidia_cmd) {
-                if let Some(line) = out.lines().next() {
-                    let parts: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
-                    if parts.len() == 5 {
-                        let name = parts[0].to_string();
-                        let memory_total_mb = parts[1].parse().unwrap_or(0);
-                        let memory_used_mb = parts[2].parse().unwrap_or(0);
-                        let utilization_percent = parts[3].parse().unwrap_or(0);
-                        let temperature_c = parts[4].parse().unwrap_or(0);
-
-                        return GpuInfo::success(
-                            name,
-                            memory_total_mb,
-                            memory_used_mb,
-                            utilization_percent,
-                            temperature_c,
-                        );
-                    }
+                let mut devices: Vec<GpuDevice> =
+                    out.lines().filter_map(parse_nvidia_line).collect();
+                if !devices.is_empty() {
+                    attach_nvidia_processes(&session, &mut devices);
+                    return GpuInfo::success(devices);
                 }
             }
 
-            // nvidia-smi not found or failed to parse; treat as unavailable
-            GpuInfo::failure("nvidia-smi not available")
+            let rocm_cmd = concat!(
+                "rocm-smi --showproductname --showuniqueid --showmeminfo vram ",
+                "--showuse --showtemp --showpower --showfan --json"
+            );
+            if let Ok(out) = run_command(&session, rocm_cmd) {
+                let devices = parse_rocm_smi_json(&out);
+                if !devices.is_empty() {
+                    return GpuInfo::success(devices);
+                }
+            }
+
+            // Neither vendor tool is installed; fall back to the DRM sysfs tree,
+            // which covers amdgpu and i915/xe without any userspace tooling.
+            let sysfs_cmd = concat!(
+                "for d in /sys/class/drm/card[0-9]*; do ",
+                "case \"$d\" in *-*) continue ;; esac; ",
+                "[ -r \"$d/device/vendor\" ] || continue; ",
+                "echo \"card=${d##*card} ",
+                "vendor=$(cat $d/device/vendor) ",
+                "device=$(cat $d/device/device 2>/dev/null) ",
+                "busy=$(cat $d/device/gpu_busy_percent 2>/dev/null) ",
+                "vram_total=$(cat $d/device/mem_info_vram_total 2>/dev/null) ",
+                "vram_used=$(cat $d/device/mem_info_vram_used 2>/dev/null) ",
+                "temp=$(cat $d/device/hwmon/hwmon*/temp1_input 2>/dev/null | head -n1) ",
+                "power=$(cat $d/device/hwmon/hwmon*/power1_average 2>/dev/null | head -n1)\"; ",
+                "done"
+            );
+            let mut devices = match run_command(&session, sysfs_cmd) {
+                Ok(out) => parse_drm_sysfs(&out),
+                Err(_) => Vec::new(),
+            };
+
+            // i915 exposes no busy counter in sysfs; sample it with intel_gpu_top
+            // when the host has exactly one Intel GPU (the tool's default device).
+            let intel: Vec<u32> = devices
+                .iter()
+                .filter(|d| d.vendor == GpuVendor::Intel)
+                .map(|d| d.index)
+                .collect();
+            if let [index] = intel.as_slice() {
+                let intel_cmd = "timeout 3 intel_gpu_top -J -s 1000 2>/dev/null";
+                // Stopped by `timeout`, so the exit status is always 124
+                if let Ok(out) = run_command_lenient(&session, intel_cmd)
+                    && let Some(busy) = parse_intel_gpu_top_busy(&out)
+                    && let Some(device) = devices.iter_mut().find(|d| d.index == *index)
+                {
+                    device.utilization_percent = busy;
+                }
+            }
+
+            if devices.is_empty() {
+                return GpuInfo::failure(NO_GPU_FOUND);
+            }
+            GpuInfo::success(devices)
         }
 
-        "Darwin" => {
+        Platform::Darwin => {
             let sp_cmd = r#"system_profiler SPDisplaysDataType | grep -E 'Chipset Model|VRAM'"#;
-            let output = match run_command(&session, sp_cmd) {
+            let output = match run_command_lenient(&session, sp_cmd) {
                 Ok(out) => out.trim().to_string(),
                 Err(e) => return GpuInfo::failure(e),
             };
@@ -96,35 +241,332 @@ pub fn fetch_gpu_info(info: &SshHostInfo) -> GpuInfo {
                 return GpuInfo::failure("No GPU info found with system_profiler");
             }
 
-            let mut name = None;
-            let mut memory_total_mb = 0;
+            // Machines with switchable graphics report one "Chipset Model" per GPU
+            let mut devices: Vec<GpuDevice> = Vec::new();
             for line in output.lines() {
                 let line = line.trim();
                 if let Some(rest) = line.strip_prefix("Chipset Model:") {
-                    name = Some(rest.trim().to_string());
-                } else if line.contains("VRAM") {
-                    if let Some(value) = line.split(':').nth(1) {
-                        let value = value.trim();
-                        let mut parts = value.split_whitespace();
-                        if let Some(num_str) = parts.next() {
-                            if let Ok(mut num) = num_str.replace(',', "").parse::<u32>() {
-                                if let Some(unit) = parts.next() {
-                                    if unit.eq_ignore_ascii_case("GB") {
-                                        num *= 1024;
-                                    }
-                                }
-                                memory_total_mb = num;
-                            }
+                    let name = rest.trim().to_string();
+                    devices.push(GpuDevice {
+                        vendor: GpuVendor::from_name(&name),
+                        index: devices.len() as u32,
+                        uuid: String::new(),
+                        name,
+                        memory_total_mb: 0,
+                        memory_used_mb: 0,
+                        utilization_percent: 0,
+                        temperature_c: 0,
+                        power_draw_w: None,
+                        fan_percent: None,
+                        processes: Vec::new(),
+                        idle_since: None,
+                    });
+                } else if line.contains("VRAM")
+                    && let Some(value) = line.split(':').nth(1)
+                {
+                    let value = value.trim();
+                    let mut parts = value.split_whitespace();
+                    if let Some(num_str) = parts.next()
+                        && let Ok(mut num) = num_str.replace(',', "").parse::<u32>()
+                    {
+                        if let Some(unit) = parts.next()
+                            && unit.eq_ignore_ascii_case("GB")
+                        {
+                            num *= 1024;
+                        }
+                        if let Some(device) = devices.last_mut() {
+                            device.memory_total_mb = num;
                         }
                     }
                 }
             }
 
-            let name = name.unwrap_or_else(|| "Unknown".to_string());
+            if devices.is_empty() {
+                return GpuInfo::failure("No GPU info found with system_profiler");
+            }
+
+            GpuInfo::success(devices)
+        }
 
-            GpuInfo::success(name, memory_total_mb, 0, 0, 0)
+        // The FreeBSD NVIDIA driver ships nvidia-smi; nothing else reports GPUs
+        Platform::FreeBsd | Platform::OpenBsd => {
+            let nvidia_cmd = concat!(
+                "nvidia-smi --query-gpu=index,uuid,name,memory.total,memory.used,",
+                "utilization.gpu,temperature.gpu,power.draw,fan.speed ",
+                "--format=csv,noheader,nounits 2>/dev/null"
+            );
+            let devices: Vec<GpuDevice> = run_command(&session, nvidia_cmd)
+                .map(|out| out.lines().filter_map(parse_nvidia_line).collect())
+                .unwrap_or_default();
+            if devices.is_empty() {
+                return GpuInfo::failure(NO_GPU_FOUND);
+            }
+            GpuInfo::success(devices)
         }
+    }
+}
+
+/// Parses one line of `nvidia-smi --query-gpu` CSV output.
+/// Fields that the driver reports as `[N/A]` become `None` or zero.
+fn parse_nvidia_line(line: &str) -> Option<GpuDevice> {
+    let parts: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+    if parts.len() != 9 {
+        return None;
+    }
+
+    Some(GpuDevice {
+        vendor: GpuVendor::Nvidia,
+        index: parts[0].parse().ok()?,
+        uuid: parts[1].to_string(),
+        name: parts[2].to_string(),
+        memory_total_mb: parts[3].parse().unwrap_or(0),
+        memory_used_mb: parts[4].parse().unwrap_or(0),
+        utilization_percent: parts[5].parse().unwrap_or(0),
+        temperature_c: parts[6].parse().unwrap_or(0),
+        power_draw_w: parts[7].parse().ok(),
+        fan_percent: parts[8].parse().ok(),
+        processes: Vec::new(),
+        idle_since: None,
+    })
+}
+
+/// Looks up the compute processes on each NVIDIA GPU and resolves their
+/// owner and command line with `ps`. Failures leave the process lists empty.
+fn attach_nvidia_processes(session: &Session, devices: &mut [GpuDevice]) {
+    let apps_cmd =
+        "nvidia-smi --query-compute-apps=pid,used_memory,gpu_uuid --format=csv,noheader,nounits";
+    let Ok(apps_out) = run_command(session, apps_cmd) else {
+        return;
+    };
+    let apps = parse_nvidia_compute_apps(&apps_out);
+    if apps.is_empty() {
+        return;
+    }
+
+    let pids: Vec<String> = apps.iter().map(|(pid, _, _)| pid.to_string()).collect();
+    let ps_cmd = format!("ps -o pid=,user=,args= -p {}", pids.join(","));
+    let owners = run_command_lenient(session, &ps_cmd)
+        .map(|out| parse_ps_owners(&out))
+        .unwrap_or_default();
+
+    for (pid, used_memory_mb, gpu_uuid) in apps {
+        let Some(device) = devices.iter_mut().find(|d| d.uuid == gpu_uuid) else {
+            continue;
+        };
+        let (user, command) = owners
+            .get(&pid)
+            .cloned()
+            .unwrap_or_else(|| ("?".to_string(), String::new()));
+        device.processes.push(GpuProcess {
+            pid,
+            user,
+            used_memory_mb,
+            command,
+        });
+    }
+}
+
+/// Parses `pid, used_memory, gpu_uuid` lines from `--query-compute-apps`.
+fn parse_nvidia_compute_apps(output: &str) -> Vec<(u32, u32, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+            if parts.len() != 3 {
+                return None;
+            }
+            Some((
+                parts[0].parse().ok()?,
+                parts[1].parse().unwrap_or(0),
+                parts[2].to_string(),
+            ))
+        })
+        .collect()
+}
+
+/// Parses `ps -o pid=,user=,args=` output into `pid -> (user, command)`.
+fn parse_ps_owners(output: &str) -> HashMap<u32, (String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let pid = parts.next()?.parse().ok()?;
+            let user = parts.next()?.to_string();
+            let command = parts.collect::<Vec<_>>().join(" ");
+            Some((pid, (user, command)))
+        })
+        .collect()
+}
+
+/// Parses `rocm-smi --json` output, which is an object keyed by `card<N>`
+/// whose values are all strings (e.g. `"GPU use (%)": "42"`).
+fn parse_rocm_smi_json(output: &str) -> Vec<GpuDevice> {
+    let Ok(Value::Object(cards)) = serde_json::from_str::<Value>(output) else {
+        return Vec::new();
+    };
+
+    let mut devices: Vec<GpuDevice> = cards
+        .iter()
+        .filter_map(|(key, card)| {
+            let index = key.strip_prefix("card")?.parse().ok()?;
+            let field = |name: &str| card.get(name).and_then(Value::as_str).map(str::trim);
+            let number = |name: &str| field(name).and_then(|v| v.parse::<f64>().ok());
+            // Power and temperature keys differ between ROCm releases
+            let first_number = |names: &[&str]| names.iter().find_map(|n| number(n));
+
+            let bytes_to_mb = |b: f64| (b / 1024.0 / 1024.0) as u32;
+            Some(GpuDevice {
+                vendor: GpuVendor::Amd,
+                index,
+                uuid: field("Unique ID").unwrap_or_default().to_string(),
+                name: field("Card series")
+                    .or_else(|| field("Card model"))
+                    .unwrap_or("AMD GPU")
+                    .to_string(),
+                memory_total_mb: number("VRAM Total Memory (B)").map_or(0, bytes_to_mb),
+                memory_used_mb: number("VRAM Total Used Memory (B)").map_or(0, bytes_to_mb),
+                utilization_percent: number("GPU use (%)").unwrap_or(0.0) as u8,
+                temperature_c: first_number(&[
+                    "Temperature (Sensor edge) (C)",
+                    "Temperature (Sensor junction) (C)",
+                ])
+                .unwrap_or(0.0) as u8,
+                power_draw_w: first_number(&[
+                    "Average Graphics Package Power (W)",
+                    "Current Socket Graphics Package Power (W)",
+                ])
+                .map(|w| w as f32),
+                fan_percent: number("Fan speed (%)").map(|f| f as u8),
+                processes: Vec::new(),
+                idle_since: None,
+            })
+        })
+        .collect();
+
+    devices.sort_by_key(|d| d.index);
+    devices
+}
+
+/// Parses the `key=value` lines emitted by the DRM sysfs probe.
+/// Temperatures are in millidegrees and power in microwatts, as hwmon reports them.
+/// Cards from other vendors are display adapters such as server BMC VGA chips
+/// (ASPEED, Matrox), not GPUs anyone can schedule work on, so they are skipped.
+fn parse_drm_sysfs(output: &str) -> Vec<GpuDevice> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: HashMap<&str, &str> = line
+                .split_whitespace()
+                .filter_map(|kv| kv.split_once('='))
+                .collect();
+            let number = |name: &str| fields.get(name).and_then(|v| v.parse::<u64>().ok());
+
+            let vendor = GpuVendor::from_pci_id(fields.get("vendor")?);
+            if vendor == GpuVendor::Unknown {
+                return None;
+            }
+            let device_id = fields.get("device").copied().unwrap_or("?");
+            Some(GpuDevice {
+                vendor,
+                index: number("card")? as u32,
+                uuid: String::new(),
+                name: format!("{vendor} {device_id}"),
+                memory_total_mb: number("vram_total").map_or(0, |b| (b / 1024 / 1024) as u32),
+                memory_used_mb: number("vram_used").map_or(0, |b| (b / 1024 / 1024) as u32),
+                utilization_percent: number("busy").unwrap_or(0) as u8,
+                temperature_c: number("temp").map_or(0, |t| (t / 1000) as u8),
+                power_draw_w: number("power").map(|p| p as f32 / 1_000_000.0),
+                fan_percent: None,
+                processes: Vec::new(),
+                idle_since: None,
+            })
+        })
+        .collect()
+}
+
+/// Extracts the most recent Render/3D engine busy percentage from
+/// `intel_gpu_top -J`. The stream is cut off by `timeout`, so the JSON is
+/// usually incomplete and is scanned rather than deserialized.
+fn parse_intel_gpu_top_busy(output: &str) -> Option<u8> {
+    INTEL_RENDER_BUSY
+        .captures_iter(output)
+        .last()
+        .and_then(|c| c[1].parse::<f32>().ok())
+        .map(|busy| busy.round() as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nvidia_line_with_missing_fields() {
+        let line = "3, GPU-1234abcd, NVIDIA A100-SXM4-80GB, 81920, 40960, 71, 83, [N/A], [N/A]";
+        let device = parse_nvidia_line(line).expect("line should parse");
+
+        assert_eq!(device.index, 3);
+        assert_eq!(device.uuid, "GPU-1234abcd");
+        assert_eq!(device.memory_used_mb, 40960);
+        assert_eq!(device.temperature_c, 83);
+        assert_eq!(device.power_draw_w, None);
+        assert_eq!(device.fan_percent, None);
+    }
+
+    #[test]
+    fn test_parse_nvidia_compute_apps_and_owners() {
+        let apps = parse_nvidia_compute_apps("4242, 30512, GPU-1234abcd\n");
+        assert_eq!(apps, vec![(4242, 30512, "GPU-1234abcd".to_string())]);
+
+        let owners = parse_ps_owners(" 4242 alice    python train.py --epochs 10\n");
+        assert_eq!(
+            owners.get(&4242),
+            Some(&(
+                "alice".to_string(),
+                "python train.py --epochs 10".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_rocm_smi_json() {
+        let json = r#"{
+            "card1": {"Card series": "AMD Instinct MI250X", "Unique ID": "0x1f2e",
+                      "VRAM Total Memory (B)": "68702699520", "VRAM Total Used Memory (B)": "10737418240",
+                      "GPU use (%)": "87", "Temperature (Sensor junction) (C)": "64.0",
+                      "Current Socket Graphics Package Power (W)": "412.0"},
+            "card0": {"Card series": "AMD Instinct MI250X", "GPU use (%)": "0"},
+            "system": {"Driver version": "6.2.4"}
+        }"#;
+        let devices = parse_rocm_smi_json(json);
+
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].index, 0);
+        assert_eq!(devices[1].vendor, GpuVendor::Amd);
+        assert_eq!(devices[1].memory_total_mb, 65520);
+        assert_eq!(devices[1].memory_used_mb, 10240);
+        assert_eq!(devices[1].utilization_percent, 87);
+        assert_eq!(devices[1].temperature_c, 64);
+        assert_eq!(devices[1].power_draw_w, Some(412.0));
+    }
+
+    #[test]
+    fn test_parse_drm_sysfs_and_intel_gpu_top() {
+        // card0 is the ASPEED VGA chip of the server's BMC
+        let sysfs = "\
+card=0 vendor=0x1a03 device=0x2000 busy= vram_total= vram_used= temp= power=
+card=1 vendor=0x8086 device=0x56a0 busy= vram_total= vram_used= temp=48000 power=
+";
+        let devices = parse_drm_sysfs(sysfs);
+
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].index, 1);
+        assert_eq!(devices[0].vendor, GpuVendor::Intel);
+        assert_eq!(devices[0].temperature_c, 48);
+        assert_eq!(devices[0].power_draw_w, None);
 
-        other => GpuInfo::failure(format!("Unsupported platform: {}", other)),
+        let top = r#"[
+{"period": {"duration": 1000.0}, "engines": {"Render/3D/0": {"busy": 12.4, "sema": 0.0}}},
+{"period": {"duration": 1000.0}, "engines": {"Render/3D/0": {"busy": 55.6, "sema": 0.0}"#;
+        assert_eq!(parse_intel_gpu_top_busy(top), Some(56));
     }
 }
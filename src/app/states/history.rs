@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+
+/// Samples older than this are dropped; it matches the longest chart window.
+const RETENTION_SECS: u64 = 24 * 60 * 60;
+
+/// Upper bound per series: a day of samples at the fastest 30s collection
+/// interval, so a host cannot grow its buffer without limit.
+const MAX_SAMPLES: usize = (RETENTION_SECS / 30) as usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Metric {
+    /// CPU usage normalized by core count, 0-100.
+    Cpu,
+    Memory,
+    /// Average utilization across the host's GPUs.
+    Gpu,
+}
+
+/// Time range shown by the details charts. Samples arrive every 30s, so
+/// shorter windows would only hold a handful of points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HistoryWindow {
+    OneHour,
+    OneDay,
+}
+
+impl HistoryWindow {
+    pub fn next(self) -> Self {
+        match self {
+            HistoryWindow::OneHour => HistoryWindow::OneDay,
+            HistoryWindow::OneDay => HistoryWindow::OneHour,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HistoryWindow::OneHour => "1h",
+            HistoryWindow::OneDay => "24h",
+        }
+    }
+
+    pub fn secs(self) -> u64 {
+        match self {
+            HistoryWindow::OneHour => 60 * 60,
+            HistoryWindow::OneDay => RETENTION_SECS,
+        }
+    }
+}
+
+/// Ring buffer of `(unix seconds, percent)` samples for one metric.
+#[derive(Debug, Clone, Default)]
+pub struct MetricHistory {
+    samples: VecDeque<(u64, f64)>,
+}
+
+impl MetricHistory {
+    pub fn push(&mut self, at: u64, value: f64) {
+        while self.samples.len() >= MAX_SAMPLES
+            || self
+                .samples
+                .front()
+                .is_some_and(|(t, _)| at.saturating_sub(*t) > RETENTION_SECS)
+        {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((at, value));
+    }
+
+    /// Samples within `window` of `now`, as (seconds before now, value) with
+    /// negative x so charts read left to right.
+    pub fn points(&self, now: u64, window: HistoryWindow) -> Vec<(f64, f64)> {
+        self.samples
+            .iter()
+            .filter(|(t, _)| now.saturating_sub(*t) <= window.secs())
+            .map(|(t, v)| (*t as f64 - now as f64, *v))
+            .collect()
+    }
+
+    /// The newest sample as (unix seconds, value).
+    pub fn last(&self) -> Option<(u64, f64)> {
+        self.samples.back().copied()
+    }
+
+    /// The last `count` values, oldest first.
+    pub fn recent(&self, count: usize) -> Vec<f64> {
+        let skip = self.samples.len().saturating_sub(count);
+        self.samples.iter().skip(skip).map(|(_, v)| *v).collect()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HostHistory {
+    pub cpu: MetricHistory,
+    pub memory: MetricHistory,
+    pub gpu: MetricHistory,
+}
+
+impl HostHistory {
+    pub fn get(&self, metric: Metric) -> &MetricHistory {
+        match metric {
+            Metric::Cpu => &self.cpu,
+            Metric::Memory => &self.memory,
+            Metric::Gpu => &self.gpu,
+        }
+    }
+
+    /// Appends `value` to `metric`, taken at `at` unix seconds.
+    pub fn record(&mut self, metric: Metric, at: u64, value: f64) {
+        let series = match metric {
+            Metric::Cpu => &mut self.cpu,
+            Metric::Memory => &mut self.memory,
+            Metric::Gpu => &mut self.gpu,
+        };
+        series.push(at, value);
+    }
+
+    /// When any metric was last collected, i.e. the host was last reachable.
+    pub fn last_seen(&self) -> Option<u64> {
+        [&self.cpu, &self.memory, &self.gpu]
+            .iter()
+            .filter_map(|m| m.last().map(|(at, _)| at))
+            .max()
+    }
+}
+
+pub type SharedHistory = Arc<Mutex<HashMap<String, HostHistory>>>;
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metric_history_is_bounded_and_windowed() {
+        let mut history = MetricHistory::default();
+        let start = 1_700_000_000;
+        for i in 0..(MAX_SAMPLES as u64 + 10) {
+            history.push(start + i, i as f64);
+        }
+        assert_eq!(history.samples.len(), MAX_SAMPLES);
+
+        let now = start + MAX_SAMPLES as u64 + 9;
+        let points = history.points(now, HistoryWindow::OneHour);
+        assert_eq!(points.len(), MAX_SAMPLES);
+        assert_eq!(points.first(), Some(&(1.0 - MAX_SAMPLES as f64, 10.0)));
+        assert_eq!(points.last().map(|p| p.0), Some(0.0));
+
+        let later = history.points(now + 1000, HistoryWindow::OneHour);
+        assert_eq!(later.len(), 2601);
+        assert_eq!(later.first(), Some(&(-3600.0, (MAX_SAMPLES - 2591) as f64)));
+        assert_eq!(
+            history.recent(2),
+            vec![(MAX_SAMPLES + 8) as f64, (MAX_SAMPLES + 9) as f64]
+        );
+
+        // A gap longer than the retention empties the buffer
+        history.push(now + RETENTION_SECS + 1, 1.0);
+        assert_eq!(history.recent(10), vec![1.0]);
+    }
+}
@@ -0,0 +1,294 @@
+use super::app_config::HistoryConfig;
+use super::history::{HostHistory, Metric, unix_now};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+/// One line of the history file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredSample {
+    pub host: String,
+    pub metric: Metric,
+    pub at: u64,
+    pub value: f64,
+    /// Raw samples averaged into this one, so re-compacting a bucket keeps
+    /// the weights. Omitted from the file for raw samples.
+    #[serde(default = "one", skip_serializing_if = "is_one")]
+    pub count: u64,
+}
+
+fn one() -> u64 {
+    1
+}
+
+fn is_one(count: &u64) -> bool {
+    *count == 1
+}
+
+/// Work for the writer thread, which owns the file so appends and
+/// compaction never run at the same time and never block the runtime.
+enum WriterMessage {
+    Append(StoredSample),
+    Compact(Sender<io::Result<()>>),
+}
+
+/// Append-only JSON-lines file of metric samples, so history survives a
+/// restart. Samples are appended as they are collected and the file is
+/// periodically rewritten with retention and downsampling applied.
+pub struct HistoryStore {
+    /// `None` when persistence is disabled or there is no data dir.
+    path: Option<PathBuf>,
+    config: HistoryConfig,
+    /// Channel to the writer thread; `None` without persistence.
+    writer: Option<Sender<WriterMessage>>,
+}
+
+pub type SharedHistoryStore = Arc<HistoryStore>;
+
+pub fn history_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("ssh-monitoring/history.jsonl"))
+}
+
+impl HistoryStore {
+    pub fn open(config: &HistoryConfig) -> Self {
+        let path = history_path().filter(|_| config.persist);
+        let writer = path.clone().map(|path| {
+            let (sender, receiver) = mpsc::channel();
+            let config = config.clone();
+            thread::spawn(move || run_writer(&path, &config, receiver));
+            sender
+        });
+        Self {
+            path,
+            config: config.clone(),
+            writer,
+        }
+    }
+
+    /// Reads the history file into per-host buffers, applying retention and
+    /// downsampling. A missing or unreadable file gives an empty history.
+    pub fn load(&self) -> HashMap<String, HostHistory> {
+        let mut history: HashMap<String, HostHistory> = HashMap::new();
+        for sample in self.read_compacted().unwrap_or_default() {
+            history
+                .entry(sample.host)
+                .or_default()
+                .record(sample.metric, sample.at, sample.value);
+        }
+        history
+    }
+
+    /// Records `value` for `host` in memory and queues it for the file.
+    pub fn record(
+        &self,
+        history: &mut HashMap<String, HostHistory>,
+        host: &str,
+        metric: Metric,
+        value: f64,
+    ) {
+        let at = unix_now();
+        history
+            .entry(host.to_string())
+            .or_default()
+            .record(metric, at, value);
+
+        if let Some(writer) = &self.writer {
+            let sample = StoredSample {
+                host: host.to_string(),
+                metric,
+                at,
+                value,
+                count: 1,
+            };
+            let _ = writer.send(WriterMessage::Append(sample));
+        }
+    }
+
+    /// Rewrites the file with only the samples that survive retention and
+    /// downsampling, keeping it from growing without bound. Blocks until the
+    /// writer thread has finished, so call it off the async runtime.
+    pub fn compact(&self) -> io::Result<()> {
+        let Some(writer) = &self.writer else {
+            return Ok(());
+        };
+        let (reply, result) = mpsc::channel();
+        writer
+            .send(WriterMessage::Compact(reply))
+            .map_err(|_| io::Error::other("history writer stopped"))?;
+        result
+            .recv()
+            .map_err(|_| io::Error::other("history writer stopped"))?
+    }
+
+    fn read_compacted(&self) -> io::Result<Vec<StoredSample>> {
+        match &self.path {
+            Some(path) => read_compacted(path, &self.config),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Writer thread: keeps the file open for appends and swaps it out when
+/// compacting. Exits once the store, and with it the sender, is dropped.
+fn run_writer(path: &Path, config: &HistoryConfig, messages: Receiver<WriterMessage>) {
+    let mut file: Option<File> = None;
+    for message in messages {
+        match message {
+            WriterMessage::Append(sample) => {
+                if let Err(e) = append(path, &mut file, &sample) {
+                    // Reopen on the next sample in case the file was moved away
+                    file = None;
+                    tracing::warn!("Failed to append history sample: {e}");
+                }
+            }
+            WriterMessage::Compact(reply) => {
+                // Drop the handle first; it would point at the replaced file
+                file = None;
+                let _ = reply.send(compact_file(path, config));
+            }
+        }
+    }
+}
+
+fn append(path: &Path, file: &mut Option<File>, sample: &StoredSample) -> io::Result<()> {
+    let handle = match file {
+        Some(handle) => handle,
+        None => {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            file.insert(OpenOptions::new().create(true).append(true).open(path)?)
+        }
+    };
+    let mut line = serde_json::to_string(sample)?;
+    line.push('\n');
+    handle.write_all(line.as_bytes())
+}
+
+fn compact_file(path: &Path, config: &HistoryConfig) -> io::Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let samples = read_compacted(path, config)?;
+
+    let mut content = String::new();
+    for sample in &samples {
+        content.push_str(&serde_json::to_string(sample)?);
+        content.push('\n');
+    }
+    // Write aside and rename so a crash mid-write keeps the old file
+    let tmp = path.with_extension("jsonl.tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)
+}
+
+fn read_compacted(path: &Path, config: &HistoryConfig) -> io::Result<Vec<StoredSample>> {
+    let content = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    // A crash can leave a partial last line; skip anything unparsable
+    let samples = content
+        .lines()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect();
+    Ok(compact_samples(samples, unix_now(), config))
+}
+
+/// Drops samples past retention and averages those older than
+/// `downsample_after_minutes` into `downsample_secs` buckets per host and
+/// metric. The result is sorted by time.
+fn compact_samples(
+    samples: Vec<StoredSample>,
+    now: u64,
+    config: &HistoryConfig,
+) -> Vec<StoredSample> {
+    let retention_start = now.saturating_sub(config.retention_hours * 3600);
+    let full_resolution_start = now.saturating_sub(config.downsample_after_minutes * 60);
+    let bucket_secs = config.downsample_secs.max(1);
+
+    let mut kept = Vec::new();
+    // (host, metric, bucket) -> (weighted timestamp sum, weighted value sum, count)
+    let mut buckets: HashMap<(String, Metric, u64), (u64, f64, u64)> = HashMap::new();
+    for sample in samples {
+        if sample.at < retention_start {
+            continue;
+        }
+        if sample.at >= full_resolution_start {
+            kept.push(sample);
+            continue;
+        }
+        let bucket = buckets
+            .entry((sample.host, sample.metric, sample.at / bucket_secs))
+            .or_default();
+        bucket.0 += sample.at * sample.count;
+        bucket.1 += sample.value * sample.count as f64;
+        bucket.2 += sample.count;
+    }
+
+    kept.extend(
+        buckets.into_iter().map(
+            |((host, metric, _), (at_sum, value_sum, count))| StoredSample {
+                host,
+                metric,
+                at: at_sum / count,
+                value: value_sum / count as f64,
+                count,
+            },
+        ),
+    );
+    kept.sort_by_key(|s| s.at);
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_samples_applies_retention_and_downsampling() {
+        let config = HistoryConfig {
+            persist: true,
+            retention_hours: 2,
+            downsample_after_minutes: 60,
+            downsample_secs: 300,
+        };
+        let now = 1_700_000_000 / 300 * 300;
+        let sample = |at: u64, value: f64| StoredSample {
+            host: "web".to_string(),
+            metric: Metric::Cpu,
+            at,
+            value,
+            count: 1,
+        };
+        let samples = vec![
+            // Past retention
+            sample(now - 3 * 3600, 99.0),
+            // Same five-minute bucket, averaged into one sample
+            sample(now - 7200 + 30, 10.0),
+            sample(now - 7200 + 60, 20.0),
+            // Recent, kept as is
+            sample(now - 60, 50.0),
+        ];
+
+        let compacted = compact_samples(samples, now, &config);
+        let averaged = StoredSample {
+            count: 2,
+            ..sample(now - 7200 + 45, 15.0)
+        };
+        assert_eq!(compacted, vec![averaged.clone(), sample(now - 60, 50.0)]);
+
+        // A bucket compacted again keeps the weight of its earlier average
+        let later = vec![averaged, sample(now - 7200 + 240, 45.0)];
+        let recompacted = compact_samples(later, now, &config);
+        assert_eq!(recompacted.len(), 1);
+        assert_eq!(recompacted[0].count, 3);
+        assert_eq!(recompacted[0].value, 25.0);
+    }
+}
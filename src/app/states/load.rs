@@ -0,0 +1,222 @@
+use super::platform::{Platform, detect_platform};
+use super::ssh_hosts::SshHostInfo;
+use super::ssh_utils::{connect_ssh_session, run_command};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone)]
+pub enum LoadInfo {
+    Loading,
+    Success {
+        load_1: f32,
+        load_5: f32,
+        load_15: f32,
+        uptime_secs: u64,
+        running_procs: u32,
+        total_procs: u32,
+        user_count: u32,
+    },
+    Failure(String),
+}
+
+pub type SharedLoadInfo = Arc<Mutex<HashMap<String, LoadInfo>>>;
+
+impl LoadInfo {
+    pub fn success(
+        load: (f32, f32, f32),
+        uptime_secs: u64,
+        running_procs: u32,
+        total_procs: u32,
+        user_count: u32,
+    ) -> Self {
+        LoadInfo::Success {
+            load_1: load.0,
+            load_5: load.1,
+            load_15: load.2,
+            uptime_secs,
+            running_procs,
+            total_procs,
+            user_count,
+        }
+    }
+
+    pub fn failure(msg: impl Into<String>) -> Self {
+        LoadInfo::Failure(msg.into())
+    }
+}
+
+fn parse_load_triple(fields: &[&str]) -> Option<(f32, f32, f32)> {
+    Some((
+        fields.first()?.parse().ok()?,
+        fields.get(1)?.parse().ok()?,
+        fields.get(2)?.parse().ok()?,
+    ))
+}
+
+pub fn fetch_load_info(info: &SshHostInfo) -> LoadInfo {
+    let session = match connect_ssh_session(info) {
+        Ok(s) => s,
+        Err(e) => return LoadInfo::failure(e),
+    };
+
+    let platform = match detect_platform(&session, info) {
+        Ok(p) => p,
+        Err(e) => return LoadInfo::failure(e),
+    };
+
+    match platform {
+        Platform::Linux | Platform::BusyBox => {
+            let load_cmd = "cat /proc/loadavg /proc/uptime && who | wc -l";
+            let output = match run_command(&session, load_cmd) {
+                Ok(out) => out,
+                Err(e) => return LoadInfo::failure(e),
+            };
+            parse_proc_load(&output).unwrap_or_else(|| {
+                LoadInfo::failure(format!("Unexpected /proc/loadavg output: {}", output))
+            })
+        }
+        Platform::Darwin | Platform::FreeBsd | Platform::OpenBsd => {
+            // grep -c exits 1 when nothing is running, which is a valid count
+            let load_cmd = concat!(
+                "sysctl -n vm.loadavg && sysctl -n kern.boottime && date +%s && ",
+                "ps -A -o state= | wc -l && { ps -A -o state= | grep -c '^R' || true; } && ",
+                "who | wc -l"
+            );
+            let output = match run_command(&session, load_cmd) {
+                Ok(out) => out,
+                Err(e) => return LoadInfo::failure(e),
+            };
+            parse_sysctl_load(&output).unwrap_or_else(|| {
+                LoadInfo::failure(format!("Unexpected sysctl output: {}", output))
+            })
+        }
+    }
+}
+
+/// Parses `/proc/loadavg` ("0.12 0.34 0.56 2/345 6789"), `/proc/uptime`
+/// ("12345.67 89012.34") and the logged-in user count, one per line.
+fn parse_proc_load(output: &str) -> Option<LoadInfo> {
+    let mut lines = output.lines();
+    let loadavg: Vec<&str> = lines.next()?.split_whitespace().collect();
+    let uptime_line = lines.next().unwrap_or("");
+    let users_line = lines.next().unwrap_or("");
+
+    let load = parse_load_triple(&loadavg)?;
+    let (running_procs, total_procs) = loadavg
+        .get(3)
+        .and_then(|s| s.split_once('/'))
+        .map(|(r, t)| (r.parse().unwrap_or(0), t.parse().unwrap_or(0)))
+        .unwrap_or((0, 0));
+    let uptime_secs = uptime_line
+        .split_whitespace()
+        .next()
+        .and_then(|s| s.parse::<f64>().ok())
+        .unwrap_or(0.0) as u64;
+    let user_count = users_line.trim().parse().unwrap_or(0);
+
+    Some(LoadInfo::success(
+        load,
+        uptime_secs,
+        running_procs,
+        total_procs,
+        user_count,
+    ))
+}
+
+/// Parses the BSD probe: `vm.loadavg` ("{ 1.23 1.45 1.67 }", without braces
+/// on OpenBSD), `kern.boottime` ("{ sec = 1700000000, usec = 0 } Tue Nov 14 ..."
+/// or plain epoch seconds on OpenBSD), `date +%s`, then the total process,
+/// running process and user counts.
+fn parse_sysctl_load(output: &str) -> Option<LoadInfo> {
+    let lines: Vec<&str> = output.lines().map(str::trim).collect();
+    if lines.len() < 6 {
+        return None;
+    }
+
+    let loadavg: Vec<&str> = lines[0]
+        .trim_matches(|c| c == '{' || c == '}')
+        .split_whitespace()
+        .collect();
+    let load = parse_load_triple(&loadavg)?;
+
+    let boot_secs = lines[1]
+        .split("sec =")
+        .nth(1)
+        .and_then(|s| s.split(',').next())
+        .unwrap_or(lines[1])
+        .trim()
+        .parse::<u64>()
+        .unwrap_or(0);
+    let now_secs = lines[2].parse::<u64>().unwrap_or(0);
+    let uptime_secs = now_secs.saturating_sub(boot_secs);
+
+    let total_procs = lines[3].parse().unwrap_or(0);
+    let running_procs = lines[4].parse().unwrap_or(0);
+    let user_count = lines[5].parse().unwrap_or(0);
+
+    Some(LoadInfo::success(
+        load,
+        uptime_secs,
+        running_procs,
+        total_procs,
+        user_count,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_and_sysctl_load() {
+        let Some(LoadInfo::Success {
+            load_1,
+            load_15,
+            uptime_secs,
+            running_procs,
+            total_procs,
+            user_count,
+            ..
+        }) = parse_proc_load("0.12 0.34 0.56 2/345 6789\n12345.67 89012.34\n3\n")
+        else {
+            panic!("/proc/loadavg should parse");
+        };
+        assert_eq!((load_1, load_15), (0.12, 0.56));
+        assert_eq!((uptime_secs, running_procs, total_procs), (12345, 2, 345));
+        assert_eq!(user_count, 3);
+
+        let darwin = "\
+{ 1.23 1.45 1.67 }
+{ sec = 1700000000, usec = 123456 } Tue Nov 14 22:13:20 2023
+1700086400
+412
+0
+2
+";
+        let Some(LoadInfo::Success {
+            load_5,
+            uptime_secs,
+            running_procs,
+            total_procs,
+            ..
+        }) = parse_sysctl_load(darwin)
+        else {
+            panic!("sysctl output should parse");
+        };
+        assert_eq!(load_5, 1.45);
+        assert_eq!(uptime_secs, 86400);
+        assert_eq!((running_procs, total_procs), (0, 412));
+
+        // OpenBSD: no braces, boot time in plain seconds
+        let openbsd = "1.00 0.50 0.25\n1700000000\n1700003600\n90\n1\n1\n";
+        assert!(matches!(
+            parse_sysctl_load(openbsd),
+            Some(LoadInfo::Success {
+                uptime_secs: 3600,
+                ..
+            })
+        ));
+        assert!(parse_proc_load("").is_none());
+    }
+}
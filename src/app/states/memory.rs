@@ -1,29 +1,54 @@
+use super::platform::{Platform, detect_platform};
 use super::ssh_hosts::SshHostInfo;
-use super::ssh_utils::{connect_ssh_session, run_command};
+use super::ssh_utils::{connect_ssh_session, run_command, run_command_lenient};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+/// Memory counters in bytes. Huge page fields are zero where unsupported.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStats {
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub available_bytes: u64,
+    pub buffers_cache_bytes: u64,
+    pub swap_total_bytes: u64,
+    pub swap_used_bytes: u64,
+    pub huge_pages_total: u64,
+    pub huge_pages_free: u64,
+    pub huge_page_size_bytes: u64,
+}
+
+impl MemoryStats {
+    pub fn usage_percent(&self) -> f32 {
+        percent(self.used_bytes, self.total_bytes)
+    }
+
+    pub fn swap_usage_percent(&self) -> f32 {
+        percent(self.swap_used_bytes, self.swap_total_bytes)
+    }
+}
+
+fn percent(used: u64, total: u64) -> f32 {
+    if total == 0 {
+        0.0
+    } else {
+        used as f32 / total as f32 * 100.0
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum MemoryInfo {
     Loading,
-    Success {
-        total: String,
-        used: String,
-        usage_percent: String,
-    },
+    Success { stats: MemoryStats },
     Failure(String),
 }
 
 pub type SharedMemoryInfo = Arc<Mutex<HashMap<String, MemoryInfo>>>;
 
 impl MemoryInfo {
-    pub fn success(total: String, used: String, usage_percent: String) -> Self {
-        MemoryInfo::Success {
-            total,
-            used,
-            usage_percent,
-        }
+    pub fn success(stats: MemoryStats) -> Self {
+        MemoryInfo::Success { stats }
     }
 
     pub fn failure(msg: impl Into<String>) -> Self {
@@ -37,117 +62,302 @@ pub fn fetch_memory_info(info: &SshHostInfo) -> MemoryInfo {
         Err(e) => return MemoryInfo::failure(e),
     };
 
-    let uname_cmd = "uname -s";
-    let platform = match run_command(&session, uname_cmd) {
-        Ok(out) => out.trim().to_string(),
-        Err(e) => return MemoryInfo::failure(format!("Failed to detect platform: {}", e)),
+    let platform = match detect_platform(&session, info) {
+        Ok(p) => p,
+        Err(e) => return MemoryInfo::failure(e),
     };
 
-    match platform.as_str() {
-        "Linux" => {
-            let mem_cmd = "free -m | awk '/Mem:/ {print $2, $3}'";
+    match platform {
+        Platform::Linux | Platform::BusyBox => {
+            let mem_cmd = "cat /proc/meminfo";
             let output = match run_command(&session, mem_cmd) {
                 Ok(out) => out,
                 Err(e) => return MemoryInfo::failure(e),
             };
-            let parts: Vec<&str> = output.split_whitespace().collect();
-            if parts.len() < 2 {
-                return MemoryInfo::failure(format!("Unexpected free output: {}", output));
+            match parse_meminfo(&output) {
+                Some(stats) => MemoryInfo::success(stats),
+                None => MemoryInfo::failure(format!("Unexpected /proc/meminfo output: {}", output)),
             }
-            let total_mb = parts[0].parse::<u64>().unwrap_or(0);
-            let used_mb = parts[1].parse::<u64>().unwrap_or(0);
-            let percent = if total_mb > 0 {
-                (used_mb as f32 / total_mb as f32) * 100.0
-            } else {
-                0.0
-            };
-            MemoryInfo::success(
-                format!("{} MB", total_mb),
-                format!("{} MB", used_mb),
-                format!("{:.1}%", percent),
-            )
         }
-        "Darwin" => {
-            let total_cmd = "sysctl -n hw.memsize";
-            let vm_cmd = "vm_stat";
-
-            // ────────────────────────────
-            // 💾 Total memory (bytes)
-            // ────────────────────────────
-            let total_str = match run_command(&session, total_cmd) {
-                Ok(out) => out.trim().to_string(),
+        Platform::Darwin => {
+            let mem_cmd =
+                "sysctl -n hw.memsize; echo ---; sysctl -n vm.swapusage; echo ---; vm_stat";
+            let output = match run_command(&session, mem_cmd) {
+                Ok(out) => out,
                 Err(e) => return MemoryInfo::failure(e),
             };
-            let total_bytes = total_str.parse::<u64>().unwrap_or(0);
-            let total_mb = total_bytes / 1024 / 1024;
-
-            // ────────────────────────────
-            // 📊 Parse vm_stat output
-            // ────────────────────────────
-            let vm_output = match run_command(&session, vm_cmd) {
+            match parse_darwin_memory(&output) {
+                Some(stats) => MemoryInfo::success(stats),
+                None => MemoryInfo::failure(format!("Unexpected vm_stat output: {}", output)),
+            }
+        }
+        Platform::FreeBsd | Platform::OpenBsd => {
+            let pages_cmd = if platform == Platform::FreeBsd {
+                "echo \"free $(sysctl -n vm.stats.vm.v_free_count)\"; \
+                 echo \"inactive $(sysctl -n vm.stats.vm.v_inactive_count)\"; \
+                 echo ---; swapinfo -k"
+            } else {
+                "vmstat -s | awk '/pages free$/{print \"free\", $1} \
+                 /pages inactive$/{print \"inactive\", $1}'; echo ---; swapctl -lk"
+            };
+            let mem_cmd = format!(
+                "echo \"physmem $(sysctl -n hw.physmem)\"; \
+                 echo \"pagesize $(sysctl -n hw.pagesize)\"; {pages_cmd}"
+            );
+            let output = match run_command_lenient(&session, &mem_cmd) {
                 Ok(out) => out,
                 Err(e) => return MemoryInfo::failure(e),
             };
+            match parse_bsd_memory(&output) {
+                Some(stats) => MemoryInfo::success(stats),
+                None => MemoryInfo::failure(format!("Unexpected sysctl output: {}", output)),
+            }
+        }
+    }
+}
+
+/// Parses `/proc/meminfo`. Values are in kB except the `HugePages_*` counts.
+/// Kernels older than 3.14 lack `MemAvailable`; free + cache is used instead.
+fn parse_meminfo(output: &str) -> Option<MemoryStats> {
+    let fields: HashMap<&str, u64> = output
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let value = value.split_whitespace().next()?.parse().ok()?;
+            Some((key.trim(), value))
+        })
+        .collect();
+    let kb = |key: &str| fields.get(key).copied().unwrap_or(0) * 1024;
+
+    let total_bytes = kb("MemTotal");
+    if total_bytes == 0 {
+        return None;
+    }
+    let buffers_cache_bytes = kb("Buffers") + kb("Cached") + kb("SReclaimable");
+    let available_bytes = match fields.get("MemAvailable") {
+        Some(avail) => avail * 1024,
+        None => kb("MemFree") + buffers_cache_bytes,
+    };
+    let swap_total_bytes = kb("SwapTotal");
+
+    Some(MemoryStats {
+        total_bytes,
+        used_bytes: total_bytes.saturating_sub(available_bytes),
+        available_bytes,
+        buffers_cache_bytes,
+        swap_total_bytes,
+        swap_used_bytes: swap_total_bytes.saturating_sub(kb("SwapFree")),
+        huge_pages_total: fields.get("HugePages_Total").copied().unwrap_or(0),
+        huge_pages_free: fields.get("HugePages_Free").copied().unwrap_or(0),
+        huge_page_size_bytes: kb("Hugepagesize"),
+    })
+}
 
-            let mut page_size = 4096u64;
-            let mut pages_active = 0u64;
-            let mut pages_speculative = 0u64;
-            let mut pages_compressed = 0u64;
-            let mut pages_wired = 0u64;
-            let mut file_cache_pages = 0u64;
-
-            use std::collections::HashMap;
-
-            let mut counters: HashMap<&str, &mut u64> = HashMap::from([
-                ("Pages active", &mut pages_active),
-                ("Pages speculative", &mut pages_speculative),
-                ("Pages occupied by compressor", &mut pages_compressed),
-                ("Pages wired down", &mut pages_wired),
-                ("File-backed pages", &mut file_cache_pages),
-            ]);
-
-            for line in vm_output.lines() {
-                if line.contains("page size of") {
-                    if let Some(num) = line.split("page size of").nth(1) {
-                        if let Some(v) = num.split_whitespace().next() {
-                            page_size = v.parse::<u64>().unwrap_or(4096);
-                        }
-                    }
-                } else if let Some((key, value)) = line.split_once(':') {
-                    if let Some(counter) = counters.get_mut(key.trim()) {
-                        let count = value
-                            .trim()
-                            .trim_end_matches('.')
-                            .replace(".", "")
-                            .parse::<u64>()
-                            .unwrap_or(0);
-                        **counter = count;
-                    }
-                }
+/// Parses `hw.memsize`, `vm.swapusage` and `vm_stat` separated by `---`.
+/// Available memory is free + inactive + speculative + purgeable pages, which
+/// is what macOS can hand out without swapping.
+fn parse_darwin_memory(output: &str) -> Option<MemoryStats> {
+    let sections: Vec<&str> = output.split("---").collect();
+    if sections.len() < 3 {
+        return None;
+    }
+    let total_bytes: u64 = sections[0].trim().parse().ok()?;
+    let (swap_total_bytes, swap_used_bytes) = parse_swapusage(sections[1]).unwrap_or((0, 0));
+
+    let mut page_size = 4096u64;
+    let mut pages: HashMap<&str, u64> = HashMap::new();
+    for line in sections[2].lines() {
+        if let Some((_, rest)) = line.split_once("page size of") {
+            if let Some(v) = rest.split_whitespace().next() {
+                page_size = v.parse().unwrap_or(4096);
             }
+        } else if let Some((key, value)) = line.split_once(':')
+            && let Ok(count) = value.trim().trim_end_matches('.').parse::<u64>()
+        {
+            pages.insert(key.trim(), count);
+        }
+    }
+    let bytes = |key: &str| pages.get(key).copied().unwrap_or(0) * page_size;
 
-            // ────────────────────────────
-            // 🧮 Calculate used memory
-            // ────────────────────────────
-            let used_pages = pages_active
-                + pages_speculative
-                + pages_wired
-                + pages_compressed
-                + file_cache_pages;
-            let used_mb = (used_pages * page_size) / 1024 / 1024;
-
-            let percent = if total_mb > 0 {
-                (used_mb as f32 / total_mb as f32) * 100.0
-            } else {
-                0.0
-            };
+    let available_bytes = (bytes("Pages free")
+        + bytes("Pages inactive")
+        + bytes("Pages speculative")
+        + bytes("Pages purgeable"))
+    .min(total_bytes);
+
+    Some(MemoryStats {
+        total_bytes,
+        used_bytes: total_bytes - available_bytes,
+        available_bytes,
+        buffers_cache_bytes: bytes("File-backed pages"),
+        swap_total_bytes,
+        swap_used_bytes,
+        ..Default::default()
+    })
+}
 
-            MemoryInfo::success(
-                format!("{} MB", total_mb),
-                format!("{} MB", used_mb),
-                format!("{:.1}%", percent),
-            )
+/// Parses `name value` lines from sysctl and vmstat, then `---` and the
+/// `swapinfo -k` (FreeBSD) or `swapctl -lk` (OpenBSD) device table. Free and
+/// inactive pages count as available, as on macOS.
+fn parse_bsd_memory(output: &str) -> Option<MemoryStats> {
+    let (counters, swap) = output.split_once("---")?;
+    let values: HashMap<&str, u64> = counters
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.trim().split_once(' ')?;
+            Some((key, value.trim().parse().ok()?))
+        })
+        .collect();
+    let total_bytes = *values.get("physmem").filter(|t| **t > 0)?;
+    let page_size = values.get("pagesize").copied().unwrap_or(4096);
+    let pages = |key: &str| values.get(key).copied().unwrap_or(0);
+    let available_bytes = ((pages("free") + pages("inactive")) * page_size).min(total_bytes);
+
+    // Device 1K-blocks Used Avail Capacity; several devices add a Total row
+    let (swap_total_kb, swap_used_kb) = swap
+        .lines()
+        .map(|l| l.split_whitespace().collect::<Vec<&str>>())
+        .filter(|f| f.len() >= 4 && f[0] != "Total")
+        .filter_map(|f| Some((f[1].parse::<u64>().ok()?, f[2].parse::<u64>().ok()?)))
+        .fold((0, 0), |(t, u), (total, used)| (t + total, u + used));
+
+    Some(MemoryStats {
+        total_bytes,
+        used_bytes: total_bytes - available_bytes,
+        available_bytes,
+        swap_total_bytes: swap_total_kb * 1024,
+        swap_used_bytes: swap_used_kb * 1024,
+        ..Default::default()
+    })
+}
+
+/// Parses `total = 2048.00M  used = 1096.25M  free = 951.75M  (encrypted)`.
+fn parse_swapusage(output: &str) -> Option<(u64, u64)> {
+    let mut total = None;
+    let mut used = None;
+    let words: Vec<&str> = output.split_whitespace().collect();
+    for window in words.windows(3) {
+        if let [key, "=", value] = window {
+            let bytes = parse_size_suffix(value)?;
+            match *key {
+                "total" => total = Some(bytes),
+                "used" => used = Some(bytes),
+                _ => {}
+            }
         }
-        other => MemoryInfo::failure(format!("Unsupported platform: {}", other)),
+    }
+    Some((total?, used?))
+}
+
+/// Parses sizes like `2048.00M` or `1.50G` into bytes.
+fn parse_size_suffix(value: &str) -> Option<u64> {
+    let (number, multiplier) = match value.chars().last()? {
+        'K' => (&value[..value.len() - 1], 1024.0),
+        'M' => (&value[..value.len() - 1], 1024.0 * 1024.0),
+        'G' => (&value[..value.len() - 1], 1024.0 * 1024.0 * 1024.0),
+        _ => (value, 1.0),
+    };
+    Some((number.parse::<f64>().ok()? * multiplier) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_meminfo_with_and_without_mem_available() {
+        const KIB: u64 = 1024;
+        let output = "\
+MemTotal:       16384000 kB
+MemFree:         1024000 kB
+MemAvailable:    8192000 kB
+Buffers:          512000 kB
+Cached:          4096000 kB
+SwapCached:            0 kB
+SwapTotal:       2097152 kB
+SwapFree:        1048576 kB
+SReclaimable:    1024000 kB
+HugePages_Total:     512
+HugePages_Free:      128
+Hugepagesize:       2048 kB
+";
+        let stats = parse_meminfo(output).expect("meminfo should parse");
+
+        assert_eq!(stats.total_bytes, 16384000 * KIB);
+        assert_eq!(stats.available_bytes, 8192000 * KIB);
+        assert_eq!(stats.used_bytes, 8192000 * KIB);
+        assert_eq!(stats.buffers_cache_bytes, 5632000 * KIB);
+        assert_eq!(stats.swap_used_bytes, 1048576 * KIB);
+        assert_eq!(stats.huge_pages_total, 512);
+        assert_eq!(stats.huge_pages_free, 128);
+        assert_eq!(stats.huge_page_size_bytes, 2048 * KIB);
+
+        // Pre-3.14 kernels: available is free + buffers + cache + reclaimable slab
+        let old_kernel = "\
+MemTotal:        4000000 kB
+MemFree:          500000 kB
+Buffers:          100000 kB
+Cached:          1000000 kB
+SwapTotal:             0 kB
+SwapFree:              0 kB
+SReclaimable:     200000 kB
+";
+        let stats = parse_meminfo(old_kernel).expect("meminfo should parse");
+
+        assert_eq!(stats.available_bytes, 1800000 * KIB);
+        assert_eq!(stats.used_bytes, 2200000 * KIB);
+        assert_eq!(stats.swap_used_bytes, 0);
+        assert_eq!(stats.huge_pages_total, 0);
+
+        assert!(parse_meminfo("MemFree: 500000 kB\n").is_none());
+    }
+
+    #[test]
+    fn test_parse_darwin_memory() {
+        let output = "\
+17179869184
+---
+total = 2048.00M  used = 1024.00M  free = 1024.00M  (encrypted)
+---
+Mach Virtual Memory Statistics: (page size of 16384 bytes)
+Pages free:                               65536.
+Pages active:                            400000.
+Pages inactive:                          131072.
+Pages speculative:                        65536.
+Pages wired down:                        150000.
+Pages purgeable:                              0.
+File-backed pages:                       196608.
+";
+        let stats = parse_darwin_memory(output).expect("output should parse");
+
+        assert_eq!(stats.total_bytes, 16 * 1024 * 1024 * 1024);
+        // 262144 pages of 16 KiB are available
+        assert_eq!(stats.available_bytes, 4 * 1024 * 1024 * 1024);
+        assert_eq!(stats.used_bytes, 12 * 1024 * 1024 * 1024);
+        assert_eq!(stats.buffers_cache_bytes, 3 * 1024 * 1024 * 1024);
+        assert_eq!(stats.swap_total_bytes, 2 * 1024 * 1024 * 1024);
+        assert_eq!(stats.swap_used_bytes, 1024 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_parse_bsd_memory_sums_swap_devices() {
+        let output = "\
+physmem 8589934592
+pagesize 4096
+free 524288
+inactive 262144
+---
+Device          1K-blocks     Used    Avail Capacity
+/dev/ada0p3       2097152   524288  1572864    25%
+/dev/ada1p3       2097152        0  2097152     0%
+Total             4194304   524288  3670016    13%
+";
+        let stats = parse_bsd_memory(output).expect("output should parse");
+
+        assert_eq!(stats.total_bytes, 8 * 1024 * 1024 * 1024);
+        assert_eq!(stats.available_bytes, 3 * 1024 * 1024 * 1024);
+        assert_eq!(stats.used_bytes, 5 * 1024 * 1024 * 1024);
+        assert_eq!(stats.swap_total_bytes, 4 * 1024 * 1024 * 1024);
+        assert_eq!(stats.swap_used_bytes, 512 * 1024 * 1024);
     }
 }
@@ -1,16 +1,58 @@
+pub mod app_config;
+pub mod auth_log;
+pub mod clock;
+pub mod containers;
 pub mod cpu;
 pub mod disk;
+pub mod disk_io;
 pub mod gpu;
+pub mod history;
+pub mod history_store;
+pub mod load;
 pub mod memory;
+pub mod network;
 pub mod os;
+pub mod platform;
+pub mod ports;
+pub mod process_actions;
+pub mod processes;
+pub mod sensors;
+pub mod services;
+pub mod sessions;
 pub mod ssh_hosts;
 pub mod ssh_status;
 pub mod ssh_utils;
+pub mod updates;
 
+pub use app_config::{SharedAppConfig, load_app_config};
+pub use auth_log::{AuthLogInfo, SharedAuthLogInfo, fetch_auth_log_info};
+pub use clock::{ClockInfo, SharedClockInfo, fetch_clock_info};
+pub use containers::{
+    ContainerInfo, NO_CONTAINER_RUNTIME, SharedContainerInfo, fetch_container_info,
+};
 pub use cpu::{CpuInfo, SharedCpuInfo, fetch_cpu_info};
 pub use disk::{DiskInfo, SharedDiskInfo, fetch_disk_info};
-pub use gpu::{GpuInfo, SharedGpuInfo, fetch_gpu_info};
+pub use disk_io::{DiskIoInfo, SharedDiskIoInfo, fetch_disk_io_info};
+pub use gpu::{GpuDevice, GpuInfo, NO_GPU_FOUND, SharedGpuInfo, fetch_gpu_info};
+pub use history::{HistoryWindow, HostHistory, Metric, SharedHistory, unix_now};
+pub use history_store::{HistoryStore, SharedHistoryStore};
+pub use load::{LoadInfo, SharedLoadInfo, fetch_load_info};
 pub use memory::{MemoryInfo, SharedMemoryInfo, fetch_memory_info};
-pub use os::{OsInfo, SharedOsInfo, fetch_os_info};
+pub use network::{NetworkInfo, SharedNetworkInfo, fetch_network_info};
+pub use os::{MachineKind, OsInfo, SharedOsInfo, fetch_os_info};
+pub use ports::{PortInfo, SharedPortInfo, fetch_port_info};
+pub use process_actions::{
+    NICE_MAX, NICE_MIN, ProcessAction, SharedProcessActionOutput, run_process_action,
+};
+pub use processes::{
+    ProcessEntry, ProcessInfo, SharedFocusedHost, SharedProcessInfo, fetch_process_info,
+};
+pub use sensors::{
+    FanReading, NO_SENSORS_FOUND, SensorInfo, SensorKind, SharedSensorInfo, TemperatureReading,
+    fetch_sensor_info,
+};
+pub use services::{NO_SYSTEMD, ServiceInfo, SharedServiceInfo, fetch_service_info};
+pub use sessions::{SessionInfo, SharedSessionInfo, fetch_session_info};
 pub use ssh_hosts::{SharedSshHosts, SshHostInfo, load_ssh_configs};
 pub use ssh_status::{SharedSshStatuses, SshStatus, verify_connection};
+pub use updates::{NO_PACKAGE_MANAGER, SharedUpdateInfo, UpdateInfo, fetch_update_info};
@@ -0,0 +1,420 @@
+use super::platform::{Platform, detect_platform};
+use super::ssh_hosts::SshHostInfo;
+use super::ssh_utils::{connect_ssh_session, run_command, run_command_lenient};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Pause between the two counter samples on the BSDs and macOS.
+const SAMPLE_SECS: f64 = 1.0;
+
+/// Sub-second timestamp for a sample: perl ships with macOS and OpenBSD,
+/// whose `date` has no `%N`; FreeBSD 14 `date` supports it.
+const SAMPLE_STAMP: &str =
+    r#"perl -MTime::HiRes=time -e 'printf "%.6f\n", time' 2>/dev/null || date +%s.%N"#;
+
+#[derive(Debug, Clone)]
+pub struct NetInterface {
+    pub name: String,
+    pub link_up: bool,
+    pub rx_bytes_per_sec: f64,
+    pub tx_bytes_per_sec: f64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    /// Not reported by `netstat -ib` on macOS.
+    pub rx_drops: Option<u64>,
+    pub tx_drops: Option<u64>,
+    pub ipv4: Vec<String>,
+    pub ipv6: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum NetworkInfo {
+    Loading,
+    Success { interfaces: Vec<NetInterface> },
+    Failure(String),
+}
+
+pub type SharedNetworkInfo = Arc<Mutex<HashMap<String, NetworkInfo>>>;
+
+impl NetworkInfo {
+    pub fn success(interfaces: Vec<NetInterface>) -> Self {
+        NetworkInfo::Success { interfaces }
+    }
+
+    pub fn failure(msg: impl Into<String>) -> Self {
+        NetworkInfo::Failure(msg.into())
+    }
+
+    /// Combined rx + tx across all interfaces, in bytes per second.
+    pub fn total_bytes_per_sec(&self) -> f64 {
+        match self {
+            NetworkInfo::Success { interfaces } => interfaces
+                .iter()
+                .map(|i| i.rx_bytes_per_sec + i.tx_bytes_per_sec)
+                .sum(),
+            _ => 0.0,
+        }
+    }
+}
+
+/// Cumulative counters for one interface.
+#[derive(Default)]
+struct NetCounters {
+    rx_bytes: u64,
+    tx_bytes: u64,
+    rx_errors: u64,
+    tx_errors: u64,
+    rx_drops: Option<u64>,
+    tx_drops: Option<u64>,
+}
+
+pub fn fetch_network_info(info: &SshHostInfo) -> NetworkInfo {
+    let session = match connect_ssh_session(info) {
+        Ok(s) => s,
+        Err(e) => return NetworkInfo::failure(e),
+    };
+
+    let platform = match detect_platform(&session, info) {
+        Ok(p) => p,
+        Err(e) => return NetworkInfo::failure(e),
+    };
+
+    let (mut interfaces, elapsed, before, after) = match platform {
+        Platform::Linux | Platform::BusyBox => {
+            let net_cmd = concat!(
+                "cat /proc/uptime /proc/net/dev; sleep 1; echo ---; ",
+                "cat /proc/uptime /proc/net/dev; echo ---; ",
+                "for i in /sys/class/net/*; do echo \"${i##*/} $(cat $i/operstate)\"; done; ",
+                "echo ---; ip -o addr show 2>/dev/null"
+            );
+            let output = match run_command_lenient(&session, net_cmd) {
+                Ok(out) => out,
+                Err(e) => return NetworkInfo::failure(e),
+            };
+
+            let sections: Vec<&str> = output.split("---").collect();
+            if sections.len() < 4 {
+                return NetworkInfo::failure(format!("Unexpected /proc/net/dev output: {output}"));
+            }
+            let (Some((t0, before)), Some((t1, after))) = (
+                parse_proc_net_dev(sections[0]),
+                parse_proc_net_dev(sections[1]),
+            ) else {
+                return NetworkInfo::failure(format!("Unexpected /proc/net/dev output: {output}"));
+            };
+            let interfaces = parse_linux_links(sections[2], sections[3]);
+            (interfaces, t1 - t0, before, after)
+        }
+        Platform::Darwin | Platform::FreeBsd | Platform::OpenBsd => {
+            // No /proc/uptime here, so each sample is stamped instead
+            let net_cmd = format!(
+                "{SAMPLE_STAMP}; netstat -ibn; sleep {SAMPLE_SECS}; echo ---; \
+                 {SAMPLE_STAMP}; netstat -ibn; echo ---; ifconfig"
+            );
+            let output = match run_command(&session, &net_cmd) {
+                Ok(out) => out,
+                Err(e) => return NetworkInfo::failure(e),
+            };
+
+            let sections: Vec<&str> = output.split("---").collect();
+            if sections.len() < 3 {
+                return NetworkInfo::failure(format!("Unexpected netstat output: {output}"));
+            }
+            let Some(elapsed) = sample_elapsed(sections[0], sections[1]) else {
+                return NetworkInfo::failure(format!("Unexpected date output: {output}"));
+            };
+            let before = parse_netstat_ib(sections[0]);
+            let after = parse_netstat_ib(sections[1]);
+            let interfaces = parse_ifconfig(sections[2]);
+            (interfaces, elapsed, before, after)
+        }
+    };
+
+    if elapsed <= 0.0 {
+        return NetworkInfo::failure("Network samples taken at the same instant");
+    }
+
+    interfaces.retain(|i| !is_loopback(&i.name) && after.contains_key(&i.name));
+    for iface in &mut interfaces {
+        let (Some(now), Some(prev)) = (after.get(&iface.name), before.get(&iface.name)) else {
+            continue;
+        };
+        iface.rx_bytes_per_sec = now.rx_bytes.saturating_sub(prev.rx_bytes) as f64 / elapsed;
+        iface.tx_bytes_per_sec = now.tx_bytes.saturating_sub(prev.tx_bytes) as f64 / elapsed;
+        iface.rx_errors = now.rx_errors;
+        iface.tx_errors = now.tx_errors;
+        iface.rx_drops = now.rx_drops;
+        iface.tx_drops = now.tx_drops;
+    }
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+
+    NetworkInfo::success(interfaces)
+}
+
+fn is_loopback(name: &str) -> bool {
+    name == "lo" || name.starts_with("lo0")
+}
+
+fn empty_interface(name: &str, link_up: bool) -> NetInterface {
+    NetInterface {
+        name: name.to_string(),
+        link_up,
+        rx_bytes_per_sec: 0.0,
+        tx_bytes_per_sec: 0.0,
+        rx_errors: 0,
+        tx_errors: 0,
+        rx_drops: None,
+        tx_drops: None,
+        ipv4: Vec::new(),
+        ipv6: Vec::new(),
+    }
+}
+
+/// Parses `/proc/uptime` followed by `/proc/net/dev`. After the `name:` prefix,
+/// receive fields come first (bytes, packets, errs, drop, ...) and transmit
+/// fields start at index 8.
+fn parse_proc_net_dev(section: &str) -> Option<(f64, HashMap<String, NetCounters>)> {
+    let mut lines = section.lines().filter(|l| !l.trim().is_empty());
+    let uptime = lines.next()?.split_whitespace().next()?.parse().ok()?;
+    let counters = lines
+        .filter_map(|line| {
+            let (name, rest) = line.split_once(':')?;
+            let f: Vec<u64> = rest
+                .split_whitespace()
+                .filter_map(|v| v.parse().ok())
+                .collect();
+            if f.len() < 12 {
+                return None;
+            }
+            Some((
+                name.trim().to_string(),
+                NetCounters {
+                    rx_bytes: f[0],
+                    rx_errors: f[2],
+                    rx_drops: Some(f[3]),
+                    tx_bytes: f[8],
+                    tx_errors: f[10],
+                    tx_drops: Some(f[11]),
+                },
+            ))
+        })
+        .collect();
+    Some((uptime, counters))
+}
+
+/// Builds interfaces from `<name> <operstate>` lines and attaches addresses
+/// from `ip -o addr show` (`2: eth0    inet 10.0.0.5/24 brd ...`).
+fn parse_linux_links(operstates: &str, addrs: &str) -> Vec<NetInterface> {
+    let mut interfaces: Vec<NetInterface> = operstates
+        .lines()
+        .filter_map(|line| {
+            // Tunnels such as wireguard report "unknown" while passing traffic
+            let (name, state) = line.trim().split_once(' ')?;
+            Some(empty_interface(
+                name,
+                matches!(state.trim(), "up" | "unknown"),
+            ))
+        })
+        .collect();
+
+    for line in addrs.lines() {
+        let f: Vec<&str> = line.split_whitespace().collect();
+        if f.len() < 4 {
+            continue;
+        }
+        let Some(iface) = interfaces.iter_mut().find(|i| i.name == f[1]) else {
+            continue;
+        };
+        match f[2] {
+            "inet" => iface.ipv4.push(f[3].to_string()),
+            "inet6" => iface.ipv6.push(f[3].to_string()),
+            _ => {}
+        }
+    }
+    interfaces
+}
+
+/// Time between two samples from the stamps that precede them. A stamp in
+/// whole seconds (`date` printing a literal "N") can be off by a full second,
+/// so the sleep between the samples is used instead.
+fn sample_elapsed(before: &str, after: &str) -> Option<f64> {
+    let stamp = |section: &str| -> Option<(f64, bool)> {
+        let line = section.lines().find(|l| !l.trim().is_empty())?.trim();
+        match line.parse::<f64>() {
+            Ok(t) => Some((t, line.contains('.'))),
+            Err(_) => Some((line.split('.').next()?.parse().ok()?, false)),
+        }
+    };
+    let (t0, precise_before) = stamp(before)?;
+    let (t1, precise_after) = stamp(after)?;
+    Some(if precise_before && precise_after {
+        t1 - t0
+    } else {
+        SAMPLE_SECS
+    })
+}
+
+/// Parses `netstat -ibn`. Only the `<Link#N>` row of each interface carries
+/// per-interface totals; its Address column is empty for some interfaces, so
+/// counters are located by their header's distance from the end of the line.
+/// The columns vary: macOS has `Ipkts Ierrs Ibytes Opkts Oerrs Obytes Coll`,
+/// FreeBSD adds `Idrop`, and OpenBSD only reports `Ibytes Obytes`.
+fn parse_netstat_ib(section: &str) -> HashMap<String, NetCounters> {
+    let Some(header) = section.lines().find(|l| l.trim_start().starts_with("Name")) else {
+        return HashMap::new();
+    };
+    let header: Vec<&str> = header.split_whitespace().collect();
+    let from_end = |name: &str| {
+        header
+            .iter()
+            .position(|h| *h == name)
+            .map(|i| header.len() - i)
+    };
+    let (Some(rx_bytes), Some(tx_bytes)) = (from_end("Ibytes"), from_end("Obytes")) else {
+        return HashMap::new();
+    };
+    let (rx_errors, tx_errors) = (from_end("Ierrs"), from_end("Oerrs"));
+    let rx_drops = from_end("Idrop");
+
+    section
+        .lines()
+        .filter(|line| line.contains("<Link"))
+        .filter_map(|line| {
+            let f: Vec<&str> = line.split_whitespace().collect();
+            let n = f.len();
+            let value = |offset: usize| f.get(n.checked_sub(offset)?)?.parse::<u64>().ok();
+            Some((
+                f[0].trim_end_matches('*').to_string(),
+                NetCounters {
+                    rx_bytes: value(rx_bytes)?,
+                    tx_bytes: value(tx_bytes)?,
+                    rx_errors: rx_errors.and_then(value).unwrap_or(0),
+                    tx_errors: tx_errors.and_then(value).unwrap_or(0),
+                    rx_drops: rx_drops.and_then(value),
+                    tx_drops: None,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Parses BSD `ifconfig` blocks for addresses and link state.
+fn parse_ifconfig(section: &str) -> Vec<NetInterface> {
+    let mut interfaces: Vec<NetInterface> = Vec::new();
+    for line in section.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            if let Some((name, rest)) = line.split_once(": ") {
+                let up = rest.contains("<UP") || rest.contains(",UP");
+                interfaces.push(empty_interface(name, up));
+            }
+            continue;
+        }
+        let Some(iface) = interfaces.last_mut() else {
+            continue;
+        };
+        let f: Vec<&str> = line.split_whitespace().collect();
+        match f.as_slice() {
+            ["inet", addr, ..] => iface.ipv4.push(addr.to_string()),
+            ["inet6", addr, ..] => iface
+                .ipv6
+                .push(addr.split('%').next().unwrap_or(addr).to_string()),
+            ["status:", status, ..] => iface.link_up = *status == "active",
+            _ => {}
+        }
+    }
+    interfaces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_netstat_ib_with_missing_address() {
+        let output = "\
+Name       Mtu   Network       Address            Ipkts Ierrs     Ibytes    Opkts Oerrs     Obytes  Coll
+lo0        16384 <Link#1>                        123456     0   98765432   123456     0   98765432     0
+lo0        16384 127           127.0.0.1         123456     -   98765432   123456     -   98765432     -
+en0        1500  <Link#6>    a4:83:e7:12:34:56  9876543     2 8765432109  5432109     1  987654321     0
+";
+        let counters = parse_netstat_ib(output);
+
+        assert_eq!(counters.len(), 2);
+        let en0 = &counters["en0"];
+        assert_eq!(en0.rx_bytes, 8765432109);
+        assert_eq!(en0.tx_bytes, 987654321);
+        assert_eq!(en0.rx_errors, 2);
+        assert_eq!(en0.tx_errors, 1);
+        assert_eq!(counters["lo0"].rx_bytes, 98765432);
+    }
+
+    #[test]
+    fn test_sample_elapsed_falls_back_without_sub_seconds() {
+        let elapsed = sample_elapsed("1714557723.250000\nName", "\n1714557724.400000\nName");
+        assert!((elapsed.unwrap() - 1.15).abs() < 1e-6);
+
+        // `date +%s.%N` on macOS: 1.1s apart, but the stamps differ by 2
+        assert_eq!(
+            sample_elapsed("1714557723.N\nName", "1714557725.N\nName"),
+            Some(SAMPLE_SECS)
+        );
+        assert_eq!(
+            sample_elapsed("1714557723\n", "1714557724\n"),
+            Some(SAMPLE_SECS)
+        );
+        assert_eq!(sample_elapsed("netstat: error", "1714557724\n"), None);
+    }
+
+    #[test]
+    fn test_parse_ifconfig_addresses_and_status() {
+        let output = "\
+lo0: flags=8049<UP,LOOPBACK,RUNNING,MULTICAST> mtu 16384
+\tinet 127.0.0.1 netmask 0xff000000
+en0: flags=8863<UP,BROADCAST,SMART,RUNNING,SIMPLEX,MULTICAST> mtu 1500
+\tether a4:83:e7:12:34:56
+\tinet6 fe80::1c2b:3a4d:5e6f:7081%en0 prefixlen 64 secured scopeid 0x6
+\tinet 192.168.1.20 netmask 0xffffff00 broadcast 192.168.1.255
+\tstatus: active
+en1: flags=8863<UP,BROADCAST,SMART,RUNNING,SIMPLEX,MULTICAST> mtu 1500
+\tstatus: inactive
+bridge0: flags=8822<BROADCAST,SMART,SIMPLEX,MULTICAST> mtu 1500
+";
+        let interfaces = parse_ifconfig(output);
+
+        assert_eq!(interfaces.len(), 4);
+        let en0 = &interfaces[1];
+        assert_eq!(en0.name, "en0");
+        assert!(en0.link_up);
+        assert_eq!(en0.ipv4, vec!["192.168.1.20"]);
+        assert_eq!(en0.ipv6, vec!["fe80::1c2b:3a4d:5e6f:7081"]);
+        assert!(!interfaces[2].link_up, "status: inactive overrides UP");
+        assert!(!interfaces[3].link_up);
+    }
+
+    #[test]
+    fn test_parse_linux_links_with_addresses() {
+        let operstates = "\
+eth0 up
+wg0 unknown
+eth1 down
+";
+        let addrs = "\
+1: lo    inet 127.0.0.1/8 scope host lo\\       valid_lft forever preferred_lft forever
+2: eth0    inet 10.0.0.5/24 brd 10.0.0.255 scope global eth0\\       valid_lft forever preferred_lft forever
+2: eth0    inet6 fe80::a00:27ff:fe4e:66a1/64 scope link \\       valid_lft forever preferred_lft forever
+4: wg0    inet 10.8.0.2/24 scope global wg0\\       valid_lft forever preferred_lft forever
+";
+        let interfaces = parse_linux_links(operstates, addrs);
+
+        assert_eq!(interfaces.len(), 3);
+        assert_eq!(interfaces[0].name, "eth0");
+        assert!(interfaces[0].link_up);
+        assert_eq!(interfaces[0].ipv4, vec!["10.0.0.5/24"]);
+        assert_eq!(interfaces[0].ipv6, vec!["fe80::a00:27ff:fe4e:66a1/64"]);
+        assert!(interfaces[1].link_up, "wireguard reports unknown");
+        assert_eq!(interfaces[1].ipv4, vec!["10.8.0.2/24"]);
+        assert!(!interfaces[2].link_up);
+        assert!(interfaces[2].ipv4.is_empty());
+    }
+}
@@ -1,27 +1,56 @@
+use super::platform::{Platform, detect_platform};
 use super::ssh_hosts::SshHostInfo;
 use super::ssh_utils::{connect_ssh_session, run_command};
 use std::collections::HashMap;
+use std::fmt;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MachineKind {
+    BareMetal,
+    VirtualMachine,
+    Container,
+    #[default]
+    Unknown,
+}
+
+impl fmt::Display for MachineKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            MachineKind::BareMetal => "bare metal",
+            MachineKind::VirtualMachine => "VM",
+            MachineKind::Container => "container",
+            MachineKind::Unknown => "unknown",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OsDetails {
+    pub name: String,
+    pub version: String,
+    pub timezone: String,
+    pub kernel: String,
+    pub arch: String,
+    pub hostname: String,
+    pub cpu_model: String,
+    /// Hypervisor or container technology, e.g. "kvm" or "docker".
+    pub virtualization: Option<String>,
+    pub machine: MachineKind,
+}
+
 #[derive(Debug, Clone)]
 pub enum OsInfo {
     Loading,
-    Success {
-        name: String,
-        version: String,
-        timezone: String,
-    },
+    Success { details: OsDetails },
     Failure(String),
 }
 
 impl OsInfo {
-    pub fn success(name: String, version: String, timezone: String) -> Self {
-        OsInfo::Success {
-            name,
-            version,
-            timezone,
-        }
+    pub fn success(details: OsDetails) -> Self {
+        OsInfo::Success { details }
     }
 
     pub fn failure(msg: impl Into<String>) -> Self {
@@ -37,15 +66,13 @@ pub fn fetch_os_info(info: &SshHostInfo) -> OsInfo {
         Err(e) => return OsInfo::failure(e),
     };
 
-    // First, try to detect platform with uname
-    let uname_cmd = "uname -s";
-    let platform = match run_command(&session, uname_cmd) {
-        Ok(out) => out.trim().to_string(),
-        Err(e) => return OsInfo::failure(format!("Failed to detect platform: {}", e)),
+    let platform = match detect_platform(&session, info) {
+        Ok(p) => p,
+        Err(e) => return OsInfo::failure(e),
     };
 
-    match platform.as_str() {
-        "Linux" => {
+    let (name, version, timezone, inventory_cmd) = match platform {
+        Platform::Linux | Platform::BusyBox => {
             let os_cmd =
                 r#"awk -F= '/^NAME=|^VERSION_ID=/{gsub(/"/, "", $2); print $2}' /etc/os-release"#;
             let output = match run_command(&session, os_cmd) {
@@ -67,10 +94,31 @@ pub fn fetch_os_info(info: &SshHostInfo) -> OsInfo {
                 Err(_) => "Unknown".to_string(),
             };
 
-            OsInfo::success(name.to_string(), version.to_string(), timezone)
+            // Without systemd-detect-virt, fall back to container marker files
+            // and the CPU hypervisor flag.
+            let inventory_cmd = concat!(
+                "echo \"kernel=$(uname -r)\"; echo \"arch=$(uname -m)\"; ",
+                "echo \"hostname=$(hostname -f 2>/dev/null || uname -n)\"; ",
+                "cpu=$(grep -m1 -E '^(model name|Hardware|cpu model)' /proc/cpuinfo | cut -d: -f2-); ",
+                "[ -z \"$cpu\" ] && cpu=$(lscpu 2>/dev/null | grep -m1 'Model name' | cut -d: -f2-); ",
+                "echo \"cpu=$cpu\"; ",
+                "if command -v systemd-detect-virt >/dev/null 2>&1; then ",
+                "echo \"container=$(systemd-detect-virt -c 2>/dev/null)\"; ",
+                "echo \"vm=$(systemd-detect-virt -v 2>/dev/null)\"; ",
+                "else if [ -f /.dockerenv ]; then echo container=docker; ",
+                "elif [ -f /run/.containerenv ]; then echo container=podman; ",
+                "else echo container=none; fi; ",
+                "grep -qw hypervisor /proc/cpuinfo && echo vm=hypervisor || echo vm=none; fi"
+            );
+            (
+                name.to_string(),
+                version.to_string(),
+                timezone,
+                inventory_cmd,
+            )
         }
 
-        "Darwin" => {
+        Platform::Darwin => {
             let os_cmd = r#"sw_vers -productName && sw_vers -productVersion"#;
             let output = match run_command(&session, os_cmd) {
                 Ok(out) => out,
@@ -98,9 +146,156 @@ pub fn fetch_os_info(info: &SshHostInfo) -> OsInfo {
                 Err(_) => "Unknown".to_string(),
             };
 
-            OsInfo::success(name.to_string(), version.to_string(), timezone)
+            let inventory_cmd = concat!(
+                "echo \"kernel=$(uname -r)\"; echo \"arch=$(uname -m)\"; ",
+                "echo \"hostname=$(hostname)\"; ",
+                "echo \"cpu=$(sysctl -n machdep.cpu.brand_string)\"; ",
+                "echo container=none; ",
+                "[ \"$(sysctl -n kern.hv_vmm_present 2>/dev/null)\" = 1 ] ",
+                "&& echo vm=hypervisor || echo vm=none"
+            );
+            (
+                name.to_string(),
+                version.to_string(),
+                timezone,
+                inventory_cmd,
+            )
+        }
+
+        Platform::FreeBsd | Platform::OpenBsd => {
+            // freebsd-version -u reports the userland patch level, e.g. "14.1-RELEASE-p3"
+            let os_cmd = "uname -s; freebsd-version -u 2>/dev/null || uname -r";
+            let output = match run_command(&session, os_cmd) {
+                Ok(out) => out,
+                Err(e) => return OsInfo::failure(e),
+            };
+
+            let mut lines = output.lines();
+            let name = lines.next().unwrap_or("").trim();
+            let version = lines.next().unwrap_or("").trim();
+
+            if name.is_empty() || version.is_empty() {
+                return OsInfo::failure(format!("Unexpected BSD uname output: {}", output));
+            }
+
+            // FreeBSD copies the zone file and records its name separately
+            let tz_cmd = "cat /var/db/zoneinfo 2>/dev/null || readlink /etc/localtime";
+            let timezone = match run_command(&session, tz_cmd) {
+                Ok(out) => {
+                    let out = out.trim();
+                    out.split("zoneinfo/").nth(1).unwrap_or(out).to_string()
+                }
+                Err(_) => "Unknown".to_string(),
+            };
+            let timezone = if timezone.is_empty() {
+                "Unknown".to_string()
+            } else {
+                timezone
+            };
+
+            // kern.vm_guest is FreeBSD only; OpenBSD leaves the machine kind unknown
+            let inventory_cmd = if platform == Platform::FreeBsd {
+                concat!(
+                    "echo \"kernel=$(uname -r)\"; echo \"arch=$(uname -m)\"; ",
+                    "echo \"hostname=$(hostname)\"; ",
+                    "echo \"cpu=$(sysctl -n hw.model)\"; ",
+                    "[ \"$(sysctl -n security.jail.jailed 2>/dev/null)\" = 1 ] ",
+                    "&& echo container=jail || echo container=none; ",
+                    "echo \"vm=$(sysctl -n kern.vm_guest 2>/dev/null)\""
+                )
+            } else {
+                concat!(
+                    "echo \"kernel=$(uname -r)\"; echo \"arch=$(uname -m)\"; ",
+                    "echo \"hostname=$(hostname)\"; ",
+                    "echo \"cpu=$(sysctl -n hw.model)\""
+                )
+            };
+            (
+                name.to_string(),
+                version.to_string(),
+                timezone,
+                inventory_cmd,
+            )
+        }
+    };
+
+    let mut details = OsDetails {
+        name,
+        version,
+        timezone,
+        ..OsDetails::default()
+    };
+    // The base OS fields are still useful when the inventory probe fails
+    if let Ok(out) = run_command(&session, inventory_cmd) {
+        parse_inventory(&mut details, &out);
+    }
+    OsInfo::success(details)
+}
+
+/// Reads the `key=value` lines of the inventory probe into `details`.
+fn parse_inventory(details: &mut OsDetails, output: &str) {
+    let mut container = None;
+    let mut vm = None;
+
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match key {
+            "kernel" => details.kernel = value,
+            "arch" => details.arch = value,
+            "hostname" => details.hostname = value,
+            "cpu" => details.cpu_model = value,
+            "container" => container = Some(value),
+            "vm" => vm = Some(value),
+            _ => {}
         }
+    }
+
+    let detected = |v: &Option<String>| {
+        v.as_deref()
+            .filter(|v| !v.is_empty() && *v != "none")
+            .map(String::from)
+    };
+    (details.machine, details.virtualization) = match (detected(&container), detected(&vm)) {
+        (Some(c), _) => (MachineKind::Container, Some(c)),
+        (None, Some(v)) => (MachineKind::VirtualMachine, Some(v)),
+        // Both probes ran and found nothing
+        (None, None) if container.is_some() && vm.is_some() => (MachineKind::BareMetal, None),
+        (None, None) => (MachineKind::Unknown, None),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_inventory_detects_machine_kind() {
+        let output = "\
+kernel=6.8.0-45-generic
+arch=x86_64
+hostname=gpu1.lab.example.com
+cpu= AMD EPYC 7763 64-Core Processor
+container=none
+vm=kvm
+";
+        let mut details = OsDetails::default();
+        parse_inventory(&mut details, output);
+
+        assert_eq!(details.kernel, "6.8.0-45-generic");
+        assert_eq!(details.hostname, "gpu1.lab.example.com");
+        assert_eq!(details.cpu_model, "AMD EPYC 7763 64-Core Processor");
+        assert_eq!(details.machine, MachineKind::VirtualMachine);
+        assert_eq!(details.virtualization.as_deref(), Some("kvm"));
+
+        let mut details = OsDetails::default();
+        parse_inventory(&mut details, "container=none\nvm=none\n");
+        assert_eq!(details.machine, MachineKind::BareMetal);
 
-        other => OsInfo::failure(format!("Unsupported platform: {}", other)),
+        let mut details = OsDetails::default();
+        parse_inventory(&mut details, "container=docker\nvm=none\n");
+        assert_eq!(details.machine, MachineKind::Container);
     }
 }
@@ -0,0 +1,77 @@
+use super::ssh_hosts::SshHostInfo;
+use super::ssh_utils::run_command;
+use ssh2::Session;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+/// Operating system family of a host, which decides the commands each
+/// collector runs. BusyBox is Linux whose userland lacks the GNU flags.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Platform {
+    Linux,
+    BusyBox,
+    Darwin,
+    FreeBsd,
+    OpenBsd,
+}
+
+impl Platform {
+    /// Linux kernel interfaces such as `/proc` and `/sys` are available.
+    pub fn is_linux(self) -> bool {
+        matches!(self, Platform::Linux | Platform::BusyBox)
+    }
+}
+
+/// Platforms already detected, keyed by host id. A host does not change OS
+/// while the app runs, so every collector after the first skips the probe.
+static DETECTED: LazyLock<Mutex<HashMap<String, Platform>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Returns the platform of `info`, probing it over `session` the first time.
+pub fn detect_platform(session: &Session, info: &SshHostInfo) -> Result<Platform, String> {
+    if let Some(platform) = DETECTED.lock().ok().and_then(|d| d.get(&info.id).copied()) {
+        return Ok(platform);
+    }
+
+    // BusyBox applets answer --help with a "BusyBox vX.Y" banner
+    let probe_cmd = "uname -s; df --help 2>&1 | head -n 1";
+    let output =
+        run_command(session, probe_cmd).map_err(|e| format!("Failed to detect platform: {}", e))?;
+    let platform = parse_platform(&output)?;
+
+    if let Ok(mut detected) = DETECTED.lock() {
+        detected.insert(info.id.clone(), platform);
+    }
+    Ok(platform)
+}
+
+fn parse_platform(output: &str) -> Result<Platform, String> {
+    let mut lines = output.lines();
+    let kernel = lines.next().unwrap_or("").trim();
+    let busybox = lines.next().is_some_and(|l| l.contains("BusyBox"));
+    match kernel {
+        "Linux" if busybox => Ok(Platform::BusyBox),
+        "Linux" => Ok(Platform::Linux),
+        "Darwin" => Ok(Platform::Darwin),
+        "FreeBSD" => Ok(Platform::FreeBsd),
+        "OpenBSD" => Ok(Platform::OpenBsd),
+        other => Err(format!("Unsupported platform: {}", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_platform_detects_busybox() {
+        let busybox = "Linux\nBusyBox v1.36.1 (2023-11-07 18:53:09 UTC) multi-call binary.\n";
+        let gnu = "Linux\nUsage: df [OPTION]... [FILE]...\n";
+        let freebsd = "FreeBSD\ndf: illegal option -- -\n";
+
+        assert_eq!(parse_platform(busybox), Ok(Platform::BusyBox));
+        assert_eq!(parse_platform(gnu), Ok(Platform::Linux));
+        assert_eq!(parse_platform(freebsd), Ok(Platform::FreeBsd));
+        assert!(parse_platform("SunOS\n").is_err());
+    }
+}
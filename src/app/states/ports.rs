@@ -0,0 +1,266 @@
+use super::platform::{Platform, detect_platform};
+use super::ssh_hosts::SshHostInfo;
+use super::ssh_utils::{connect_ssh_session, run_command, run_command_lenient};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Bind addresses that accept connections on every interface.
+const WILDCARD_ADDRESSES: [&str; 4] = ["0.0.0.0", "*", "::", "[::]"];
+
+#[derive(Debug, Clone)]
+pub struct ListeningPort {
+    pub protocol: String,
+    pub address: String,
+    pub port: u16,
+    /// Only visible for processes owned by the SSH user unless run as root.
+    pub process: Option<String>,
+    pub pid: Option<u32>,
+    /// Bound to all interfaces rather than a specific address.
+    pub exposed: bool,
+    /// Listed in the configured allowlist.
+    pub allowed: bool,
+}
+
+impl ListeningPort {
+    pub fn is_flagged(&self) -> bool {
+        self.exposed && !self.allowed
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum PortInfo {
+    Loading,
+    Success { ports: Vec<ListeningPort> },
+    Failure(String),
+}
+
+pub type SharedPortInfo = Arc<Mutex<HashMap<String, PortInfo>>>;
+
+impl PortInfo {
+    pub fn success(ports: Vec<ListeningPort>) -> Self {
+        PortInfo::Success { ports }
+    }
+
+    pub fn failure(msg: impl Into<String>) -> Self {
+        PortInfo::Failure(msg.into())
+    }
+}
+
+pub fn fetch_port_info(info: &SshHostInfo, allowed_ports: &[u16]) -> PortInfo {
+    let session = match connect_ssh_session(info) {
+        Ok(s) => s,
+        Err(e) => return PortInfo::failure(e),
+    };
+
+    let platform = match detect_platform(&session, info) {
+        Ok(p) => p,
+        Err(e) => return PortInfo::failure(e),
+    };
+
+    let mut ports = match platform {
+        Platform::Linux | Platform::BusyBox => {
+            // BusyBox systems rarely ship ss, but the netstat applet lists the same
+            let ss_cmd = "if command -v ss >/dev/null 2>&1; then ss -tulpnH; \
+                          else echo ---; netstat -tulnp 2>/dev/null; fi";
+            match run_command(&session, ss_cmd) {
+                Ok(out) => match out.strip_prefix("---") {
+                    Some(netstat) => parse_netstat_linux(netstat),
+                    None => parse_ss(&out),
+                },
+                Err(e) => return PortInfo::failure(e),
+            }
+        }
+        Platform::Darwin => {
+            let lsof_cmd = "lsof -nP -iTCP -sTCP:LISTEN 2>/dev/null";
+            // lsof exits 1 when some processes cannot be inspected as non-root
+            match run_command_lenient(&session, lsof_cmd) {
+                Ok(out) => parse_lsof(&out),
+                Err(e) => return PortInfo::failure(e),
+            }
+        }
+        Platform::FreeBsd => {
+            let sockstat_cmd = "sockstat -46l";
+            match run_command(&session, sockstat_cmd) {
+                Ok(out) => parse_sockstat(&out),
+                Err(e) => return PortInfo::failure(e),
+            }
+        }
+        // fstat can map sockets to processes but needs root for other users
+        Platform::OpenBsd => {
+            let netstat_cmd = "netstat -an | grep -E '^(tcp.*LISTEN|udp)'";
+            match run_command_lenient(&session, netstat_cmd) {
+                Ok(out) => parse_netstat_bsd(&out),
+                Err(e) => return PortInfo::failure(e),
+            }
+        }
+    };
+
+    for port in &mut ports {
+        port.allowed = allowed_ports.contains(&port.port);
+    }
+    // Flagged first, then by port
+    ports.sort_by_key(|p| {
+        (
+            !p.is_flagged(),
+            p.port,
+            p.protocol.clone(),
+            p.address.clone(),
+        )
+    });
+    ports.dedup_by(|a, b| a.protocol == b.protocol && a.address == b.address && a.port == b.port);
+    PortInfo::success(ports)
+}
+
+/// Splits `addr:port`, keeping IPv6 brackets and dropping `%iface` scopes.
+fn split_address(local: &str) -> Option<(String, u16)> {
+    let (address, port) = local.rsplit_once(':')?;
+    let address = address.split('%').next().unwrap_or(address);
+    Some((address.to_string(), port.parse().ok()?))
+}
+
+fn listening_port(
+    protocol: &str,
+    local: &str,
+    process: Option<String>,
+    pid: Option<u32>,
+) -> Option<ListeningPort> {
+    let (address, port) = split_address(local)?;
+    Some(ListeningPort {
+        protocol: protocol.to_string(),
+        exposed: WILDCARD_ADDRESSES.contains(&address.as_str()),
+        address,
+        port,
+        process,
+        pid,
+        allowed: false,
+    })
+}
+
+/// Parses `ss -tulpnH`:
+/// `tcp LISTEN 0 4096 0.0.0.0:22 0.0.0.0:* users:(("sshd",pid=1012,fd=3))`.
+fn parse_ss(output: &str) -> Vec<ListeningPort> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let f: Vec<&str> = line.split_whitespace().collect();
+            if f.len() < 6 {
+                return None;
+            }
+            let users = f.get(6).copied().unwrap_or("");
+            let process = users
+                .split('"')
+                .nth(1)
+                .filter(|p| !p.is_empty())
+                .map(String::from);
+            let pid = users
+                .split("pid=")
+                .nth(1)
+                .and_then(|rest| rest.split([',', ')']).next())
+                .and_then(|p| p.parse().ok());
+            listening_port(f[0], f[4], process, pid)
+        })
+        .collect()
+}
+
+/// Parses `netstat -tulnp` from BusyBox or net-tools. UDP rows have no state:
+/// `tcp 0 0 0.0.0.0:22 0.0.0.0:* LISTEN 812/dropbear`.
+fn parse_netstat_linux(output: &str) -> Vec<ListeningPort> {
+    output
+        .lines()
+        .filter(|line| line.starts_with("tcp") || line.starts_with("udp"))
+        .filter_map(|line| {
+            let f: Vec<&str> = line.split_whitespace().collect();
+            if f.len() < 5 {
+                return None;
+            }
+            let protocol = f[0].trim_end_matches('6');
+            let (pid, process) = f
+                .last()
+                .and_then(|p| p.split_once('/'))
+                .map(|(pid, name)| (pid.parse().ok(), Some(name.to_string())))
+                .unwrap_or((None, None));
+            listening_port(protocol, f[3], process, pid)
+        })
+        .collect()
+}
+
+/// Parses FreeBSD `sockstat -46l`:
+/// `root sshd 812 4 tcp4 *:22 *:*`.
+fn parse_sockstat(output: &str) -> Vec<ListeningPort> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let f: Vec<&str> = line.split_whitespace().collect();
+            if f.len() < 6 {
+                return None;
+            }
+            let protocol = f[4].trim_end_matches(['4', '6']);
+            listening_port(protocol, f[5], Some(f[1].to_string()), f[2].parse().ok())
+        })
+        .collect()
+}
+
+/// Parses OpenBSD `netstat -an`, which separates the port with a dot:
+/// `tcp 0 0 *.22 *.* LISTEN`.
+fn parse_netstat_bsd(output: &str) -> Vec<ListeningPort> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let f: Vec<&str> = line.split_whitespace().collect();
+            if f.len() < 5 {
+                return None;
+            }
+            let (address, port) = f[3].rsplit_once('.')?;
+            let protocol = f[0].trim_end_matches('6');
+            listening_port(protocol, &format!("{address}:{port}"), None, None)
+        })
+        .collect()
+}
+
+/// Parses `lsof -nP -iTCP -sTCP:LISTEN`:
+/// `sshd 123 root 3u IPv4 0x1234 0t0 TCP *:22 (LISTEN)`.
+fn parse_lsof(output: &str) -> Vec<ListeningPort> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let f: Vec<&str> = line.split_whitespace().collect();
+            if f.len() < 9 {
+                return None;
+            }
+            listening_port(
+                &f[7].to_lowercase(),
+                f[8],
+                Some(f[0].to_string()),
+                f[1].parse().ok(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ss_listening_sockets() {
+        let output = r#"udp   UNCONN 0      0      127.0.0.53%lo:53        0.0.0.0:*
+tcp   LISTEN 0      4096         0.0.0.0:22        0.0.0.0:*    users:(("sshd",pid=1012,fd=3))
+tcp   LISTEN 0      511             [::]:8080         [::]:*
+"#;
+        let ports = parse_ss(output);
+
+        assert_eq!(ports.len(), 3);
+        assert_eq!(ports[0].address, "127.0.0.53");
+        assert_eq!(ports[0].port, 53);
+        assert!(!ports[0].exposed);
+        assert_eq!(ports[1].process.as_deref(), Some("sshd"));
+        assert_eq!(ports[1].pid, Some(1012));
+        assert!(ports[1].exposed);
+        assert_eq!(ports[2].address, "[::]");
+        assert!(ports[2].exposed);
+        assert_eq!(ports[2].process, None);
+    }
+}
@@ -0,0 +1,176 @@
+use super::platform::{Platform, detect_platform};
+use super::processes::{ProcessEntry, parse_lstart, parse_proc_stat};
+use super::ssh_hosts::SshHostInfo;
+use super::ssh_utils::{connect_ssh_session, exec_command, run_command_lenient, shell_quote};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Niceness range accepted by `renice`.
+pub const NICE_MIN: i32 = -20;
+pub const NICE_MAX: i32 = 19;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProcessAction {
+    Terminate,
+    Kill,
+    Renice(i32),
+    Environment,
+    OpenFiles,
+}
+
+impl ProcessAction {
+    pub fn label(&self) -> String {
+        match self {
+            ProcessAction::Terminate => "Send SIGTERM".to_string(),
+            ProcessAction::Kill => "Send SIGKILL".to_string(),
+            ProcessAction::Renice(nice) => format!("Renice to {nice}"),
+            ProcessAction::Environment => "Show environment".to_string(),
+            ProcessAction::OpenFiles => "Show open files".to_string(),
+        }
+    }
+
+    /// Shell command performing the action on `platform`.
+    fn command(&self, pid: u32, platform: Platform) -> String {
+        match (self, platform) {
+            (ProcessAction::Terminate, _) => format!("kill -TERM {pid}"),
+            (ProcessAction::Kill, _) => format!("kill -KILL {pid}"),
+            (ProcessAction::Renice(nice), _) => format!("renice -n {nice} -p {pid}"),
+            (ProcessAction::Environment, Platform::Darwin) => {
+                format!("ps -E -ww -o command= -p {pid}")
+            }
+            (ProcessAction::Environment, Platform::FreeBsd) => format!("procstat -e {pid}"),
+            (ProcessAction::Environment, Platform::OpenBsd) => {
+                format!("ps -e -ww -o command= -p {pid}")
+            }
+            (ProcessAction::Environment, _) => format!("tr '\\0' '\\n' < /proc/{pid}/environ"),
+            (ProcessAction::OpenFiles, Platform::Darwin) => format!("lsof -n -P -p {pid}"),
+            (ProcessAction::OpenFiles, Platform::FreeBsd) => format!("procstat -f {pid}"),
+            (ProcessAction::OpenFiles, Platform::OpenBsd) => format!("fstat -p {pid}"),
+            (ProcessAction::OpenFiles, _) => format!("ls -l /proc/{pid}/fd"),
+        }
+    }
+
+    /// Whether `command` relies on shell syntax, here the `/proc` redirect.
+    fn needs_shell(&self, platform: Platform) -> bool {
+        matches!(self, ProcessAction::Environment) && platform.is_linux()
+    }
+}
+
+/// Results of confirmed actions keyed by request number, so a late result
+/// from an earlier action cannot replace a newer one. No entry while running.
+pub type SharedProcessActionOutput = Arc<Mutex<HashMap<u64, Result<String, String>>>>;
+
+/// Runs `action` against `process` on the host once the PID is confirmed to
+/// still belong to it: the listing can be a minute old and PIDs get reused.
+/// With `use_sudo` the command goes through `sudo -n`, which fails instead of
+/// prompting when a password is needed; only commands that need a shell get
+/// one, with the command quoted.
+/// Output includes stderr so permission errors are shown to the user.
+pub fn run_process_action(
+    info: &SshHostInfo,
+    process: &ProcessEntry,
+    action: &ProcessAction,
+    use_sudo: bool,
+) -> Result<String, String> {
+    let session = connect_ssh_session(info)?;
+
+    let platform = detect_platform(&session, info)?;
+    let pid = process.pid;
+    let identity_cmd = if platform == Platform::BusyBox {
+        format!("cat /proc/{pid}/stat")
+    } else {
+        format!("ps -o lstart=,args= -p {pid}")
+    };
+    let identity = run_command_lenient(&session, &identity_cmd)?;
+    verify_identity(process, platform, &identity)?;
+
+    let command = action.command(pid, platform);
+    let command = match (use_sudo, action.needs_shell(platform)) {
+        (false, _) => command,
+        (true, false) => format!("sudo -n {command}"),
+        (true, true) => format!("sudo -n sh -c {}", shell_quote(&command)),
+    };
+
+    let output = exec_command(&session, &command)?;
+    let body = format!("{}{}", output.stdout, output.stderr);
+    let body = body.trim_end();
+
+    match output.exit_status {
+        0 if body.is_empty() => Ok(format!("{}: done", action.label())),
+        0 => Ok(body.to_string()),
+        code => Err(format!("{command} exited with status {code}\n{body}")),
+    }
+}
+
+/// Checks the live `ps -o lstart=,args=` (or BusyBox `/proc/<pid>/stat`)
+/// output for the PID against the process the user confirmed. BusyBox only
+/// listed a truncated command, so there the start time alone is compared.
+fn verify_identity(process: &ProcessEntry, platform: Platform, live: &str) -> Result<(), String> {
+    let live = live.trim();
+    if live.is_empty() {
+        return Err(format!("Process {} has already exited", process.pid));
+    }
+
+    let (started, command) = if platform == Platform::BusyBox {
+        (parse_proc_stat(live).map(|(_, started)| started), None)
+    } else {
+        let mut parts = live.split_whitespace();
+        let started = parse_lstart(&mut parts);
+        (started, Some(parts.collect::<Vec<_>>().join(" ")))
+    };
+    let same_command = command.as_ref().is_none_or(|c| *c == process.command);
+    if process.started.is_none() || started != process.started || !same_command {
+        let command = command.map(|c| format!(" ({c})")).unwrap_or_default();
+        return Err(format!(
+            "PID {} now belongs to another process{command}; refresh and try again",
+            process.pid
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_identity_rejects_reused_pid() {
+        let process = ProcessEntry {
+            pid: 4242,
+            user: "alice".to_string(),
+            cpu_percent: 97.5,
+            mem_percent: 12.3,
+            rss_kb: 2048000,
+            elapsed: "01:02:03".to_string(),
+            started: Some("Mon Oct 19 01:40:10 2026".to_string()),
+            command: "python train.py --epochs 10".to_string(),
+        };
+
+        let same = "Mon Oct 19 01:40:10 2026 python train.py --epochs 10\n";
+        let restarted = "Mon Oct 19 02:55:41 2026 python train.py --epochs 10\n";
+        let other = "Mon Oct 19 01:40:10 2026 sshd: bob [priv]\n";
+
+        assert_eq!(verify_identity(&process, Platform::Linux, same), Ok(()));
+        assert!(verify_identity(&process, Platform::Linux, restarted).is_err());
+        assert!(verify_identity(&process, Platform::Linux, other).is_err());
+        assert!(verify_identity(&process, Platform::Linux, "").is_err());
+
+        // BusyBox: the listed command was truncated, the start time decides
+        let busybox = ProcessEntry {
+            started: Some("4242".to_string()),
+            command: "/usr/bin/python3 /opt/service/very/long/pa".to_string(),
+            ..process
+        };
+        let stat = |started: &str| {
+            format!(
+                "4242 (python3) S 1 4242 4242 0 -1 4194560 100 0 0 0 5 3 0 0 20 0 1 0 {started} 1 1"
+            )
+        };
+        assert_eq!(
+            verify_identity(&busybox, Platform::BusyBox, &stat("4242")),
+            Ok(())
+        );
+        assert!(verify_identity(&busybox, Platform::BusyBox, &stat("9001")).is_err());
+    }
+}
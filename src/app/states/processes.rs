@@ -0,0 +1,244 @@
+use super::platform::{Platform, detect_platform};
+use super::ssh_hosts::SshHostInfo;
+use super::ssh_utils::{connect_ssh_session, run_command};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// How many processes are kept for each of the CPU and memory rankings.
+const TOP_PROCESS_COUNT: usize = 50;
+
+#[derive(Debug, Clone)]
+pub struct ProcessEntry {
+    pub pid: u32,
+    pub user: String,
+    pub cpu_percent: f32,
+    pub mem_percent: f32,
+    pub rss_kb: u64,
+    pub elapsed: String,
+    /// `ps -o lstart` start time, or the `/proc/<pid>/stat` starttime on
+    /// BusyBox, used to tell a reused PID from the process that was listed.
+    pub started: Option<String>,
+    pub command: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum ProcessInfo {
+    Loading,
+    Success {
+        by_cpu: Vec<ProcessEntry>,
+        by_memory: Vec<ProcessEntry>,
+    },
+    Failure(String),
+}
+
+pub type SharedProcessInfo = Arc<Mutex<HashMap<String, ProcessInfo>>>;
+
+/// Host whose detail view is open; its processes are refreshed more often.
+pub type SharedFocusedHost = Arc<Mutex<Option<String>>>;
+
+impl ProcessInfo {
+    pub fn success(by_cpu: Vec<ProcessEntry>, by_memory: Vec<ProcessEntry>) -> Self {
+        ProcessInfo::Success { by_cpu, by_memory }
+    }
+
+    pub fn failure(msg: impl Into<String>) -> Self {
+        ProcessInfo::Failure(msg.into())
+    }
+}
+
+pub fn fetch_process_info(info: &SshHostInfo) -> ProcessInfo {
+    let session = match connect_ssh_session(info) {
+        Ok(s) => s,
+        Err(e) => return ProcessInfo::failure(e),
+    };
+
+    let platform = match detect_platform(&session, info) {
+        Ok(p) => p,
+        Err(e) => return ProcessInfo::failure(e),
+    };
+
+    // `-A` rather than `-e`: on macOS and the BSDs `-e` means "show environment".
+    // BusyBox ps cannot report CPU usage, but its top can; start times come
+    // from /proc since top has none.
+    let ps_cmd = if platform == Platform::BusyBox {
+        "top -b -n 1; echo ---; cat /proc/[0-9]*/stat 2>/dev/null || true"
+    } else {
+        "ps -A -o pid=,user=,%cpu=,%mem=,rss=,etime=,lstart=,args="
+    };
+    let output = match run_command(&session, ps_cmd) {
+        Ok(out) => out,
+        Err(e) => return ProcessInfo::failure(e),
+    };
+
+    let processes = if platform == Platform::BusyBox {
+        let (top, stats) = output.split_once("\n---\n").unwrap_or((&output, ""));
+        attach_start_times(parse_busybox_top(top), stats)
+    } else {
+        parse_ps_output(&output)
+    };
+    if processes.is_empty() {
+        return ProcessInfo::failure(format!("Unexpected ps output: {}", output));
+    }
+
+    let mut by_cpu = processes.clone();
+    by_cpu.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent));
+    by_cpu.truncate(TOP_PROCESS_COUNT);
+
+    let mut by_memory = processes;
+    by_memory.sort_by_key(|p| std::cmp::Reverse(p.rss_kb));
+    by_memory.truncate(TOP_PROCESS_COUNT);
+
+    ProcessInfo::success(by_cpu, by_memory)
+}
+
+/// Parses headerless `ps -o pid=,user=,%cpu=,%mem=,rss=,etime=,lstart=,args=`
+/// lines. `lstart` is always five words under `LC_ALL=C`, and `args` is last
+/// so it keeps its embedded spaces.
+fn parse_ps_output(output: &str) -> Vec<ProcessEntry> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some(ProcessEntry {
+                pid: parts.next()?.parse().ok()?,
+                user: parts.next()?.to_string(),
+                cpu_percent: parts.next()?.parse().ok()?,
+                mem_percent: parts.next()?.parse().ok()?,
+                rss_kb: parts.next()?.parse().ok()?,
+                elapsed: parts.next()?.to_string(),
+                started: Some(parse_lstart(&mut parts)?),
+                command: parts.collect::<Vec<_>>().join(" "),
+            })
+        })
+        .collect()
+}
+
+/// Takes the five words of an `lstart` value, e.g. "Mon Oct 19 02:42:13 2026".
+pub fn parse_lstart<'a>(parts: &mut impl Iterator<Item = &'a str>) -> Option<String> {
+    let words: Vec<&str> = parts.take(5).collect();
+    (words.len() == 5).then(|| words.join(" "))
+}
+
+/// Parses the process table of BusyBox `top -b -n 1`, located by its
+/// `PID PPID USER STAT VSZ %VSZ [CPU] %CPU COMMAND` header. BusyBox reports no
+/// RSS or start time, so VSZ stands in for RSS and the elapsed time is unknown.
+fn parse_busybox_top(output: &str) -> Vec<ProcessEntry> {
+    let mut lines = output
+        .lines()
+        .skip_while(|l| !l.trim_start().starts_with("PID"));
+    let Some(header) = lines.next() else {
+        return Vec::new();
+    };
+    let header: Vec<&str> = header.split_whitespace().collect();
+    let column = |name: &str| header.iter().position(|h| *h == name);
+    let (Some(pid), Some(user), Some(vsz), Some(vsz_percent), Some(cpu), Some(command)) = (
+        column("PID"),
+        column("USER"),
+        column("VSZ"),
+        column("%VSZ"),
+        column("%CPU"),
+        column("COMMAND"),
+    ) else {
+        return Vec::new();
+    };
+    let percent = |v: &str| v.trim_end_matches('%').parse::<f32>().ok();
+
+    lines
+        .filter_map(|line| {
+            let f: Vec<&str> = line.split_whitespace().collect();
+            if f.len() <= command {
+                return None;
+            }
+            Some(ProcessEntry {
+                pid: f[pid].parse().ok()?,
+                user: f[user].to_string(),
+                cpu_percent: percent(f[cpu])?,
+                mem_percent: percent(f[vsz_percent])?,
+                rss_kb: parse_busybox_size(f[vsz])?,
+                elapsed: "-".to_string(),
+                started: None,
+                command: f[command..].join(" "),
+            })
+        })
+        .collect()
+}
+
+/// Sets BusyBox start times from `/proc/<pid>/stat` lines, since the command
+/// column of top is cut to the terminal width and cannot identify a process.
+fn attach_start_times(mut processes: Vec<ProcessEntry>, stats: &str) -> Vec<ProcessEntry> {
+    let started: HashMap<u32, String> = stats.lines().filter_map(parse_proc_stat).collect();
+    for process in &mut processes {
+        process.started = started.get(&process.pid).cloned();
+    }
+    processes
+}
+
+/// Takes the pid and starttime (field 22, in clock ticks since boot) from a
+/// `/proc/<pid>/stat` line. Fields are counted after the last `)` since the
+/// command name in parentheses may contain spaces.
+pub fn parse_proc_stat(line: &str) -> Option<(u32, String)> {
+    let pid = line.split_whitespace().next()?.parse().ok()?;
+    let (_, rest) = line.rsplit_once(')')?;
+    let started = rest.split_whitespace().nth(19)?;
+    Some((pid, started.to_string()))
+}
+
+/// Parses BusyBox sizes in KiB, which switch to `m` or `g` suffixes when wide.
+fn parse_busybox_size(value: &str) -> Option<u64> {
+    let (number, multiplier) = match value.chars().last()? {
+        'm' => (&value[..value.len() - 1], 1024.0),
+        'g' => (&value[..value.len() - 1], 1024.0 * 1024.0),
+        _ => (value, 1.0),
+    };
+    Some((number.parse::<f64>().ok()? * multiplier) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ps_output_keeps_command_spaces() {
+        let output = "\
+    1 root       0.0  0.1  12345    42-03:11:05 Mon Sep  7 23:30:01 2026 /sbin/init splash
+ 4242 alice     97.5 12.3 2048000       01:02:03 Mon Oct 19 01:40:10 2026 python train.py --epochs 10
+";
+        let processes = parse_ps_output(output);
+
+        assert_eq!(processes.len(), 2);
+        assert_eq!(processes[1].pid, 4242);
+        assert_eq!(processes[1].user, "alice");
+        assert_eq!(processes[1].rss_kb, 2048000);
+        assert_eq!(processes[1].elapsed, "01:02:03");
+        assert_eq!(
+            processes[1].started.as_deref(),
+            Some("Mon Oct 19 01:40:10 2026")
+        );
+        assert_eq!(processes[1].command, "python train.py --epochs 10");
+    }
+
+    #[test]
+    fn test_parse_busybox_top_with_start_times() {
+        let top = "\
+Mem: 201420K used, 1827600K free, 1268K shrd, 9012K buff, 121876K cached
+CPU:   5% usr   1% sys   0% nic  93% idle   0% io   0% irq   0% sirq
+Load average: 0.08 0.03 0.01 2/61 1301
+  PID  PPID USER     STAT   VSZ %VSZ CPU %CPU COMMAND
+ 1234     1 app      S    12.3m   1%   0   5% /usr/bin/python3 /opt/service/very/long/pa
+    2     0 root     SW       0   0%   1   0% [kthreadd]
+";
+        let stats = "\
+1234 (python3 (worker)) S 1 1234 1234 0 -1 4194560 100 0 0 0 5 3 0 0 20 0 1 0 4242 12345678 300
+2 (kthreadd) S 0 0 0 0 -1 2129984 0 0 0 0 0 0 0 0 20 0 1 0 2 0 0
+";
+        let processes = attach_start_times(parse_busybox_top(top), stats);
+
+        assert_eq!(processes.len(), 2);
+        assert_eq!(processes[0].pid, 1234);
+        assert_eq!(processes[0].rss_kb, 12595);
+        assert_eq!(processes[0].started.as_deref(), Some("4242"));
+        assert_eq!(processes[1].command, "[kthreadd]");
+        assert_eq!(processes[1].started.as_deref(), Some("2"));
+    }
+}
@@ -0,0 +1,424 @@
+use super::platform::{Platform, detect_platform};
+use super::ssh_hosts::SshHostInfo;
+use super::ssh_utils::{connect_ssh_session, run_command, run_command_lenient};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub const NO_SENSORS_FOUND: &str = "No temperature sensors found";
+
+/// Readings outside this range come from unconnected or broken sensors.
+const PLAUSIBLE_TEMP_C: std::ops::RangeInclusive<f32> = 1.0..=150.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SensorKind {
+    CpuPackage,
+    CpuCore,
+    Nvme,
+    Other,
+}
+
+impl fmt::Display for SensorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            SensorKind::CpuPackage => "CPU",
+            SensorKind::CpuCore => "CPU core",
+            SensorKind::Nvme => "NVMe",
+            SensorKind::Other => "Other",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TemperatureReading {
+    pub chip: String,
+    pub label: String,
+    pub kind: SensorKind,
+    pub celsius: f32,
+    pub critical_c: Option<f32>,
+}
+
+impl TemperatureReading {
+    fn new(chip: &str, label: &str, celsius: f32, critical_c: Option<f32>) -> Self {
+        TemperatureReading {
+            chip: chip.to_string(),
+            label: label.to_string(),
+            kind: classify(chip, label),
+            celsius,
+            critical_c,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FanReading {
+    pub chip: String,
+    pub label: String,
+    pub rpm: u32,
+}
+
+#[derive(Debug, Clone)]
+pub enum SensorInfo {
+    Loading,
+    Success {
+        temperatures: Vec<TemperatureReading>,
+        fans: Vec<FanReading>,
+    },
+    Failure(String),
+}
+
+pub type SharedSensorInfo = Arc<Mutex<HashMap<String, SensorInfo>>>;
+
+impl SensorInfo {
+    pub fn success(temperatures: Vec<TemperatureReading>, fans: Vec<FanReading>) -> Self {
+        SensorInfo::Success { temperatures, fans }
+    }
+
+    pub fn failure(msg: impl Into<String>) -> Self {
+        SensorInfo::Failure(msg.into())
+    }
+
+    /// Hottest reading together with its critical threshold, if known.
+    pub fn max_temperature(&self) -> Option<&TemperatureReading> {
+        match self {
+            SensorInfo::Success { temperatures, .. } => temperatures
+                .iter()
+                .max_by(|a, b| a.celsius.total_cmp(&b.celsius)),
+            _ => None,
+        }
+    }
+}
+
+pub fn fetch_sensor_info(info: &SshHostInfo) -> SensorInfo {
+    let session = match connect_ssh_session(info) {
+        Ok(s) => s,
+        Err(e) => return SensorInfo::failure(e),
+    };
+
+    let platform = match detect_platform(&session, info) {
+        Ok(p) => p,
+        Err(e) => return SensorInfo::failure(e),
+    };
+
+    let (mut temperatures, fans) = match platform {
+        Platform::Linux | Platform::BusyBox => {
+            // lm-sensors has the friendliest labels but reads the same hwmon
+            // data, so sysfs is only walked when it is missing.
+            let from_sensors = run_command(&session, "sensors -j 2>/dev/null")
+                .ok()
+                .and_then(|out| parse_sensors_json(&out));
+            let (mut temperatures, fans) = match from_sensors {
+                Some(readings) => readings,
+                None => {
+                    let hwmon_cmd = concat!(
+                        "for h in /sys/class/hwmon/hwmon*; do n=$(cat $h/name 2>/dev/null); ",
+                        "for f in $h/temp*_input $h/fan*_input; do [ -r \"$f\" ] || continue; ",
+                        "b=${f%_input}; echo \"$n-${h##*/}|${b##*/}|$(cat $f)|",
+                        "$(cat ${b}_label 2>/dev/null)|$(cat ${b}_crit 2>/dev/null)\"; ",
+                        "done; done"
+                    );
+                    match run_command(&session, hwmon_cmd) {
+                        Ok(out) => parse_hwmon(&out),
+                        Err(e) => return SensorInfo::failure(e),
+                    }
+                }
+            };
+
+            // Thermal zones fill in for SoCs without a CPU hwmon driver
+            if !temperatures
+                .iter()
+                .any(|t| t.kind == SensorKind::CpuPackage)
+            {
+                let thermal_cmd = concat!(
+                    "for z in /sys/class/thermal/thermal_zone*; do ",
+                    "echo \"$(cat $z/type 2>/dev/null) $(cat $z/temp 2>/dev/null)\"; done"
+                );
+                if let Ok(out) = run_command(&session, thermal_cmd) {
+                    temperatures.extend(parse_thermal_zones(&out));
+                }
+            }
+            (temperatures, fans)
+        }
+        Platform::Darwin => {
+            // No built-in CLI exposes SMC sensors; use osx-cpu-temp when installed
+            let temp_cmd = "osx-cpu-temp 2>/dev/null";
+            let output = run_command(&session, temp_cmd).unwrap_or_default();
+            let temperatures = output
+                .trim()
+                .trim_end_matches("°C")
+                .parse::<f32>()
+                .ok()
+                .map(|c| vec![TemperatureReading::new("smc", "CPU", c, None)])
+                .unwrap_or_default();
+            (temperatures, Vec::new())
+        }
+        Platform::FreeBsd | Platform::OpenBsd => {
+            // FreeBSD needs coretemp(4) or amdtemp(4) loaded for per-core readings
+            let sysctl_cmd = if platform == Platform::FreeBsd {
+                "sysctl dev.cpu hw.acpi.thermal 2>/dev/null | grep 'temperature:'"
+            } else {
+                "sysctl hw.sensors 2>/dev/null"
+            };
+            match run_command_lenient(&session, sysctl_cmd) {
+                Ok(out) => parse_bsd_sensors(&out),
+                Err(e) => return SensorInfo::failure(e),
+            }
+        }
+    };
+
+    temperatures.retain(|t| PLAUSIBLE_TEMP_C.contains(&t.celsius));
+    if temperatures.is_empty() && fans.is_empty() {
+        return SensorInfo::failure(NO_SENSORS_FOUND);
+    }
+    SensorInfo::success(temperatures, fans)
+}
+
+fn classify(chip: &str, label: &str) -> SensorKind {
+    let chip = chip.to_lowercase();
+    let label = label.to_lowercase();
+    let bsd_driver = chip.trim_end_matches(|c: char| c.is_ascii_digit());
+    if chip == "cpu" {
+        SensorKind::CpuCore
+    } else if matches!(bsd_driver, "cpu" | "km" | "ksmn") {
+        SensorKind::CpuPackage
+    } else if chip.starts_with("nvme") {
+        SensorKind::Nvme
+    } else if chip.starts_with("coretemp") {
+        if label.starts_with("core") {
+            SensorKind::CpuCore
+        } else {
+            SensorKind::CpuPackage
+        }
+    } else if chip.starts_with("k10temp") || chip.starts_with("zenpower") {
+        if label.starts_with("tccd") {
+            SensorKind::CpuCore
+        } else {
+            SensorKind::CpuPackage
+        }
+    } else if matches!(
+        chip.as_str(),
+        "x86_pkg_temp" | "cpu-thermal" | "cpu_thermal" | "soc_thermal" | "cpu0-thermal"
+    ) {
+        SensorKind::CpuPackage
+    } else {
+        SensorKind::Other
+    }
+}
+
+/// Parses `sensors -j`: `{chip: {feature: {tempN_input: 45.0, tempN_crit: 100.0}}}`.
+/// Returns `None` when lm-sensors is missing or reports nothing useful.
+fn parse_sensors_json(output: &str) -> Option<(Vec<TemperatureReading>, Vec<FanReading>)> {
+    let chips: HashMap<String, Value> = serde_json::from_str(output).ok()?;
+    let mut temperatures = Vec::new();
+    let mut fans = Vec::new();
+
+    for (chip, features) in &chips {
+        let Some(features) = features.as_object() else {
+            continue;
+        };
+        for (label, values) in features {
+            let Some(values) = values.as_object() else {
+                continue;
+            };
+            let value = |suffix: &str| {
+                values
+                    .iter()
+                    .find(|(k, _)| k.ends_with(suffix))
+                    .and_then(|(_, v)| v.as_f64())
+            };
+            let Some((input_key, input)) = values
+                .iter()
+                .find(|(k, _)| k.ends_with("_input"))
+                .and_then(|(k, v)| Some((k, v.as_f64()?)))
+            else {
+                continue;
+            };
+            if input_key.starts_with("temp") {
+                let crit = value("_crit").map(|c| c as f32);
+                temperatures.push(TemperatureReading::new(chip, label, input as f32, crit));
+            } else if input_key.starts_with("fan") {
+                fans.push(FanReading {
+                    chip: chip.clone(),
+                    label: label.clone(),
+                    rpm: input as u32,
+                });
+            }
+        }
+    }
+
+    if temperatures.is_empty() && fans.is_empty() {
+        return None;
+    }
+    sort_readings(&mut temperatures, &mut fans);
+    Some((temperatures, fans))
+}
+
+/// Parses `chip|tempN|input|label|crit` lines from the hwmon walk. Temperatures
+/// are in millidegrees; fans in RPM.
+fn parse_hwmon(output: &str) -> (Vec<TemperatureReading>, Vec<FanReading>) {
+    let mut temperatures = Vec::new();
+    let mut fans = Vec::new();
+
+    for line in output.lines() {
+        let f: Vec<&str> = line.split('|').map(str::trim).collect();
+        if f.len() < 5 {
+            continue;
+        }
+        let (chip, sensor, label) = (f[0], f[1], f[3]);
+        let label = if label.is_empty() { sensor } else { label };
+        let Ok(input) = f[2].parse::<f32>() else {
+            continue;
+        };
+        if sensor.starts_with("temp") {
+            let crit = f[4].parse::<f32>().ok().map(|c| c / 1000.0);
+            temperatures.push(TemperatureReading::new(chip, label, input / 1000.0, crit));
+        } else if sensor.starts_with("fan") {
+            fans.push(FanReading {
+                chip: chip.to_string(),
+                label: label.to_string(),
+                rpm: input as u32,
+            });
+        }
+    }
+
+    sort_readings(&mut temperatures, &mut fans);
+    (temperatures, fans)
+}
+
+/// Parses `<type> <millidegrees>` lines from `/sys/class/thermal`.
+fn parse_thermal_zones(output: &str) -> Vec<TemperatureReading> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (zone, temp) = line.trim().rsplit_once(' ')?;
+            let millidegrees = temp.parse::<f32>().ok()?;
+            Some(TemperatureReading::new(
+                zone,
+                zone,
+                millidegrees / 1000.0,
+                None,
+            ))
+        })
+        .collect()
+}
+
+/// Parses FreeBSD `dev.cpu.0.temperature: 45.0C` and OpenBSD
+/// `hw.sensors.cpu0.temp0=45.00 degC` / `hw.sensors.it0.fan1=2000 RPM` lines.
+fn parse_bsd_sensors(output: &str) -> (Vec<TemperatureReading>, Vec<FanReading>) {
+    let mut temperatures = Vec::new();
+    let mut fans = Vec::new();
+
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once('=').or_else(|| line.split_once(": ")) else {
+            continue;
+        };
+        let mut value = value.split_whitespace();
+        let number = value.next().unwrap_or("").trim_end_matches('C');
+        let Ok(number) = number.parse::<f32>() else {
+            continue;
+        };
+        let unit = value.next().unwrap_or("");
+
+        if let Some(sensor) = key.strip_prefix("hw.sensors.") {
+            let Some((chip, sensor)) = sensor.split_once('.') else {
+                continue;
+            };
+            if unit == "degC" {
+                temperatures.push(TemperatureReading::new(chip, sensor, number, None));
+            } else if unit == "RPM" {
+                fans.push(FanReading {
+                    chip: chip.to_string(),
+                    label: sensor.to_string(),
+                    rpm: number as u32,
+                });
+            }
+        } else if let Some(core) = key
+            .strip_prefix("dev.cpu.")
+            .and_then(|k| k.strip_suffix(".temperature"))
+        {
+            let label = format!("Core {core}");
+            temperatures.push(TemperatureReading::new("cpu", &label, number, None));
+        } else if let Some(zone) = key
+            .strip_prefix("hw.acpi.thermal.")
+            .and_then(|k| k.strip_suffix(".temperature"))
+        {
+            temperatures.push(TemperatureReading::new("acpi", zone, number, None));
+        }
+    }
+
+    sort_readings(&mut temperatures, &mut fans);
+    (temperatures, fans)
+}
+
+fn sort_readings(temperatures: &mut [TemperatureReading], fans: &mut [FanReading]) {
+    temperatures.sort_by(|a, b| (&a.chip, &a.label).cmp(&(&b.chip, &b.label)));
+    fans.sort_by(|a, b| (&a.chip, &a.label).cmp(&(&b.chip, &b.label)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sensors_json() {
+        let output = r#"{
+   "coretemp-isa-0000":{
+      "Adapter": "ISA adapter",
+      "Package id 0":{ "temp1_input": 71.000, "temp1_max": 84.000, "temp1_crit": 100.000 },
+      "Core 0":{ "temp2_input": 68.000, "temp2_crit": 100.000 }
+   },
+   "nvme-pci-0100":{
+      "Adapter": "PCI adapter",
+      "Composite":{ "temp1_input": 38.850, "temp1_crit": 84.850 }
+   },
+   "thinkpad-isa-0000":{
+      "Adapter": "ISA adapter",
+      "fan1":{ "fan1_input": 2150.000 }
+   }
+}"#;
+        let (temperatures, fans) = parse_sensors_json(output).expect("json should parse");
+
+        assert_eq!(temperatures.len(), 3);
+        assert_eq!(temperatures[0].label, "Core 0");
+        assert_eq!(temperatures[0].kind, SensorKind::CpuCore);
+        assert_eq!(temperatures[1].kind, SensorKind::CpuPackage);
+        assert_eq!(temperatures[1].critical_c, Some(100.0));
+        assert_eq!(temperatures[2].kind, SensorKind::Nvme);
+        assert_eq!(fans.len(), 1);
+        assert_eq!(fans[0].rpm, 2150);
+    }
+
+    #[test]
+    fn test_parse_bsd_sensors() {
+        let freebsd = "\
+dev.cpu.1.temperature: 47.0C
+dev.cpu.0.temperature: 45.0C
+hw.acpi.thermal.tz0.temperature: 27.9C
+";
+        let (temperatures, fans) = parse_bsd_sensors(freebsd);
+
+        assert_eq!(temperatures.len(), 3);
+        assert_eq!(temperatures[0].chip, "acpi");
+        assert_eq!(temperatures[0].label, "tz0");
+        assert_eq!(temperatures[1].label, "Core 0");
+        assert_eq!(temperatures[2].celsius, 47.0);
+        assert!(fans.is_empty());
+
+        let openbsd = "\
+hw.sensors.cpu0.temp0=45.00 degC
+hw.sensors.it0.temp1=38.00 degC (CPU)
+hw.sensors.it0.fan1=2000 RPM
+hw.sensors.it0.volt0=1.30 VDC (VCORE_A)
+";
+        let (temperatures, fans) = parse_bsd_sensors(openbsd);
+
+        assert_eq!(temperatures.len(), 2);
+        assert_eq!(temperatures[0].chip, "cpu0");
+        assert_eq!(temperatures[1].celsius, 38.0);
+        assert_eq!(fans.len(), 1);
+        assert_eq!(fans[0].rpm, 2000);
+    }
+}
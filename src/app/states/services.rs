@@ -0,0 +1,221 @@
+use super::platform::{Platform, detect_platform};
+use super::ssh_hosts::SshHostInfo;
+use super::ssh_utils::{
+    CommandError, connect_ssh_session, run_command, run_command_lenient, shell_quote,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub const NO_SYSTEMD: &str = "systemd not available";
+
+/// Journal lines fetched for each failed unit, and how many units get them.
+const JOURNAL_LINES: usize = 5;
+const JOURNAL_MAX_UNITS: usize = 8;
+
+#[derive(Debug, Clone)]
+pub struct UnitStatus {
+    pub name: String,
+    pub load_state: String,
+    pub active_state: String,
+    pub sub_state: String,
+    /// Listed in the config rather than only found by `--failed`.
+    pub watched: bool,
+    pub journal: Vec<String>,
+}
+
+impl UnitStatus {
+    pub fn is_failed(&self) -> bool {
+        self.active_state == "failed"
+    }
+
+    /// A watched unit that is not running, e.g. stopped or not installed.
+    pub fn is_down(&self) -> bool {
+        self.watched && !self.is_failed() && self.active_state != "active"
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ServiceInfo {
+    Loading,
+    Success { units: Vec<UnitStatus> },
+    Failure(String),
+}
+
+pub type SharedServiceInfo = Arc<Mutex<HashMap<String, ServiceInfo>>>;
+
+impl ServiceInfo {
+    pub fn success(units: Vec<UnitStatus>) -> Self {
+        ServiceInfo::Success { units }
+    }
+
+    pub fn failure(msg: impl Into<String>) -> Self {
+        ServiceInfo::Failure(msg.into())
+    }
+}
+
+pub fn fetch_service_info(info: &SshHostInfo, watched_units: &[String]) -> ServiceInfo {
+    let session = match connect_ssh_session(info) {
+        Ok(s) => s,
+        Err(e) => return ServiceInfo::failure(e),
+    };
+
+    let platform = match detect_platform(&session, info) {
+        Ok(p) => p,
+        Err(e) => return ServiceInfo::failure(e),
+    };
+
+    match platform {
+        Platform::Linux | Platform::BusyBox => {
+            let mut units_cmd = String::from(
+                "command -v systemctl >/dev/null || exit 3; \
+                 systemctl list-units --failed --all --plain --no-legend --no-pager; echo ---",
+            );
+            if !watched_units.is_empty() {
+                let names: Vec<String> = watched_units.iter().map(|u| shell_quote(u)).collect();
+                units_cmd.push_str(&format!(
+                    "; systemctl show --no-pager -p Id,LoadState,ActiveState,SubState -- {}",
+                    names.join(" ")
+                ));
+            }
+            let output = match run_command(&session, &units_cmd) {
+                Ok(out) => out,
+                Err(CommandError::Exit { status: 3, .. }) => {
+                    return ServiceInfo::failure(NO_SYSTEMD);
+                }
+                Err(e) => return ServiceInfo::failure(e),
+            };
+            let Some((failed, shown)) = output.split_once("---") else {
+                return ServiceInfo::failure(NO_SYSTEMD);
+            };
+
+            let mut units = parse_failed_units(failed);
+            for mut unit in parse_systemctl_show(shown) {
+                match units.iter_mut().find(|u| u.name == unit.name) {
+                    Some(existing) => existing.watched = true,
+                    None => {
+                        unit.watched = true;
+                        units.push(unit);
+                    }
+                }
+            }
+
+            let failed_names: Vec<String> = units
+                .iter()
+                .filter(|u| u.is_failed())
+                .take(JOURNAL_MAX_UNITS)
+                .map(|u| shell_quote(&u.name))
+                .collect();
+            if !failed_names.is_empty() {
+                let journal_cmd = format!(
+                    "for u in {}; do echo \"### $u\"; \
+                     journalctl -u \"$u\" -n {JOURNAL_LINES} --no-pager -o short-iso 2>&1; done",
+                    failed_names.join(" ")
+                );
+                if let Ok(out) = run_command_lenient(&session, &journal_cmd) {
+                    let mut journals = parse_journal(&out);
+                    for unit in &mut units {
+                        if let Some(lines) = journals.remove(&unit.name) {
+                            unit.journal = lines;
+                        }
+                    }
+                }
+            }
+
+            // Problems first, then watched units by name
+            units.sort_by_key(|u| (!u.is_failed(), !u.is_down(), u.name.clone()));
+            ServiceInfo::success(units)
+        }
+        Platform::Darwin | Platform::FreeBsd | Platform::OpenBsd => {
+            ServiceInfo::failure(NO_SYSTEMD)
+        }
+    }
+}
+
+/// Parses `systemctl list-units --failed --plain --no-legend`:
+/// `nginx.service loaded failed failed A high performance web server`.
+fn parse_failed_units(output: &str) -> Vec<UnitStatus> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut f = line.trim_start_matches(['●', '*', ' ']).split_whitespace();
+            Some(UnitStatus {
+                name: f.next()?.to_string(),
+                load_state: f.next()?.to_string(),
+                active_state: f.next()?.to_string(),
+                sub_state: f.next()?.to_string(),
+                watched: false,
+                journal: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+/// Parses `systemctl show -p ...` output: one `Key=value` block per unit,
+/// blocks separated by blank lines.
+fn parse_systemctl_show(output: &str) -> Vec<UnitStatus> {
+    output
+        .split("\n\n")
+        .filter_map(|block| {
+            let props: HashMap<&str, &str> = block
+                .lines()
+                .filter_map(|line| line.split_once('='))
+                .collect();
+            let prop = |key: &str| props.get(key).map(|v| v.to_string()).unwrap_or_default();
+            Some(UnitStatus {
+                name: props.get("Id")?.to_string(),
+                load_state: prop("LoadState"),
+                active_state: prop("ActiveState"),
+                sub_state: prop("SubState"),
+                watched: false,
+                journal: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+/// Splits journal output on the `### <unit>` markers written by the loop.
+fn parse_journal(output: &str) -> HashMap<String, Vec<String>> {
+    let mut journals: HashMap<String, Vec<String>> = HashMap::new();
+    let mut current: Option<String> = None;
+    for line in output.lines() {
+        if let Some(unit) = line.strip_prefix("### ") {
+            current = Some(unit.trim().to_string());
+        } else if let Some(unit) = &current
+            && !line.trim().is_empty()
+        {
+            journals
+                .entry(unit.clone())
+                .or_default()
+                .push(line.to_string());
+        }
+    }
+    journals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_systemctl_show_blocks() {
+        let output = "\
+Id=nginx.service
+LoadState=loaded
+ActiveState=active
+SubState=running
+
+Id=scheduler.service
+LoadState=not-found
+ActiveState=inactive
+SubState=dead
+";
+        let units = parse_systemctl_show(output);
+
+        assert_eq!(units.len(), 2);
+        assert_eq!(units[0].name, "nginx.service");
+        assert_eq!(units[0].sub_state, "running");
+        assert_eq!(units[1].load_state, "not-found");
+        assert_eq!(units[1].active_state, "inactive");
+    }
+}
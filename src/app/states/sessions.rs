@@ -0,0 +1,170 @@
+use super::app_config::SessionConfig;
+use super::platform::{Platform, detect_platform};
+use super::ssh_hosts::SshHostInfo;
+use super::ssh_utils::{connect_ssh_session, run_command};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone)]
+pub struct UserSession {
+    pub user: String,
+    pub tty: String,
+    /// Remote address, `None` for local consoles and multiplexer panes.
+    pub from: Option<String>,
+    pub login_time: String,
+    /// `who -u` idle column: "." for active, "old" for over a day, else HH:MM.
+    pub idle: String,
+    /// Not in the configured team list.
+    pub outsider: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum SessionInfo {
+    Loading,
+    Success { sessions: Vec<UserSession> },
+    Failure(String),
+}
+
+pub type SharedSessionInfo = Arc<Mutex<HashMap<String, SessionInfo>>>;
+
+impl SessionInfo {
+    pub fn success(sessions: Vec<UserSession>) -> Self {
+        SessionInfo::Success { sessions }
+    }
+
+    pub fn failure(msg: impl Into<String>) -> Self {
+        SessionInfo::Failure(msg.into())
+    }
+}
+
+pub fn fetch_session_info(info: &SshHostInfo, config: &SessionConfig) -> SessionInfo {
+    let session = match connect_ssh_session(info) {
+        Ok(s) => s,
+        Err(e) => return SessionInfo::failure(e),
+    };
+
+    let platform = match detect_platform(&session, info) {
+        Ok(p) => p,
+        Err(e) => return SessionInfo::failure(e),
+    };
+
+    // `who -u` has the same columns on Linux, macOS and the BSDs apart from the
+    // date format and pid; BusyBox `who` has no -u and its own column order.
+    let who_cmd = if platform == Platform::BusyBox {
+        "who"
+    } else {
+        "who -u"
+    };
+    let output = match run_command(&session, who_cmd) {
+        Ok(out) => out,
+        Err(e) => return SessionInfo::failure(e),
+    };
+
+    let mut sessions = if platform == Platform::BusyBox {
+        parse_busybox_who(&output)
+    } else {
+        parse_who_u(&output)
+    };
+    for s in &mut sessions {
+        s.outsider = !config.team_users.is_empty() && !config.team_users.contains(&s.user);
+    }
+    sessions.sort_by(|a, b| (&a.user, &a.tty).cmp(&(&b.user, &b.tty)));
+
+    SessionInfo::success(sessions)
+}
+
+/// Parses `who -u` lines. The login time spans one token group whose length
+/// differs by platform (`2024-05-01 10:02` vs `May  1 10:02`), so fields are
+/// taken from both ends: `user tty <time...> idle [pid] [(host)]`. The BSDs
+/// print no pid column.
+fn parse_who_u(output: &str) -> Vec<UserSession> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut f: Vec<&str> = line.split_whitespace().collect();
+            let from = match f.last() {
+                Some(last) if last.starts_with('(') => {
+                    let host = last.trim_start_matches('(').trim_end_matches(')');
+                    f.pop();
+                    Some(host.to_string())
+                }
+                _ => None,
+            };
+            if f.last()?.chars().all(|c| c.is_ascii_digit()) {
+                f.pop();
+            }
+            if f.len() < 4 {
+                return None;
+            }
+            let idle = f.pop()?.to_string();
+            Some(UserSession {
+                user: f[0].to_string(),
+                tty: f[1].to_string(),
+                // tmux and screen panes report e.g. "(tmux(1234).%0)" or ":0"
+                from: from
+                    .filter(|h| !h.is_empty() && !h.starts_with("tmux") && !h.starts_with(':')),
+                login_time: f[2..].join(" "),
+                idle,
+                outsider: false,
+            })
+        })
+        .collect()
+}
+
+/// Parses BusyBox `who`: `user tty idle Mon DD HH:MM:SS [host]`.
+fn parse_busybox_who(output: &str) -> Vec<UserSession> {
+    output
+        .lines()
+        .filter(|line| !line.starts_with("USER"))
+        .filter_map(|line| {
+            let f: Vec<&str> = line.split_whitespace().collect();
+            if f.len() < 6 {
+                return None;
+            }
+            Some(UserSession {
+                user: f[0].to_string(),
+                tty: f[1].to_string(),
+                from: f
+                    .get(6)
+                    .filter(|h| !h.is_empty() && !h.starts_with(':'))
+                    .map(|h| h.to_string()),
+                login_time: f[3..6].join(" "),
+                idle: f[2].to_string(),
+                outsider: false,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_who_u_linux_and_darwin() {
+        let linux = "\
+alice    pts/0        2024-05-01 10:02 00:05       12345 (10.0.0.5)
+bob      pts/1        2024-05-01 11:40   .         23456 (tmux(999).%0)
+";
+        let darwin = "carol    ttys000  May  1 09:15   old  3456 (192.168.1.20)\n";
+
+        let sessions = parse_who_u(linux);
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].login_time, "2024-05-01 10:02");
+        assert_eq!(sessions[0].idle, "00:05");
+        assert_eq!(sessions[0].from.as_deref(), Some("10.0.0.5"));
+        assert_eq!(sessions[1].from, None);
+
+        let sessions = parse_who_u(darwin);
+        assert_eq!(sessions[0].user, "carol");
+        assert_eq!(sessions[0].login_time, "May 1 09:15");
+        assert_eq!(sessions[0].idle, "old");
+
+        // FreeBSD and OpenBSD print no pid column
+        let sessions = parse_who_u("dave     pts/0    May  1 10:02   .   (10.0.0.7)\n");
+        assert_eq!(sessions[0].login_time, "May 1 10:02");
+        assert_eq!(sessions[0].idle, ".");
+        assert_eq!(sessions[0].from.as_deref(), Some("10.0.0.7"));
+    }
+}
@@ -1,13 +1,69 @@
 use crate::app::states::SshHostInfo;
-use ssh2::Session;
-use std::io::Read;
+use ssh2::{Channel, Session};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Read};
 use std::net::TcpStream;
 use std::path::PathBuf;
+use std::sync::{Condvar, LazyLock, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+/// Pause between polls of a channel that has no data ready.
+const READ_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Every collector opens its own session, and sshd starts dropping
+/// unauthenticated connections past `MaxStartups` (10 by default), so only
+/// this many handshakes to one host run at a time.
+const MAX_HANDSHAKES_PER_HOST: usize = 3;
+
+/// Handshakes in progress per `ip:port`, and a signal when one finishes.
+static HANDSHAKES: LazyLock<(Mutex<HashMap<String, usize>>, Condvar)> =
+    LazyLock::new(|| (Mutex::new(HashMap::new()), Condvar::new()));
+
+/// One of a host's handshake slots, given back when dropped.
+struct HandshakeSlot {
+    addr: String,
+}
+
+impl HandshakeSlot {
+    /// Blocks until fewer than `MAX_HANDSHAKES_PER_HOST` handshakes to `addr`
+    /// are in progress.
+    fn acquire(addr: &str) -> Self {
+        let (active, finished) = &*HANDSHAKES;
+        let mut active = active.lock().unwrap_or_else(PoisonError::into_inner);
+        while active.get(addr).copied().unwrap_or(0) >= MAX_HANDSHAKES_PER_HOST {
+            active = finished
+                .wait(active)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        *active.entry(addr.to_string()).or_default() += 1;
+        Self {
+            addr: addr.to_string(),
+        }
+    }
+}
+
+impl Drop for HandshakeSlot {
+    fn drop(&mut self) {
+        let (active, finished) = &*HANDSHAKES;
+        let mut active = active.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(count) = active.get_mut(&self.addr) {
+            *count -= 1;
+            if *count == 0 {
+                active.remove(&self.addr);
+            }
+        }
+        finished.notify_all();
+    }
+}
 
 /// Tries to establish an authenticated SSH session.
 /// Returns `Session` on success or an error string on failure.
 pub fn connect_ssh_session(info: &SshHostInfo) -> Result<Session, String> {
     let addr = format!("{}:{}", info.ip, info.port);
+    // Held until authentication is done, when sshd no longer counts it
+    let _slot = HandshakeSlot::acquire(&addr);
     let tcp = TcpStream::connect(&addr).map_err(|e| format!("TCP error: {}", e))?;
 
     let mut session = Session::new().map_err(|e| format!("Session error: {}", e))?;
@@ -43,21 +99,176 @@ pub fn connect_ssh_session(info: &SshHostInfo) -> Result<Session, String> {
     Err("SSH authentication failed".into())
 }
 
-pub fn run_command(session: &Session, command: &str) -> Result<String, String> {
+/// Result of a remote command whose exit status the caller interprets itself.
+#[derive(Debug, Clone)]
+pub struct CommandOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_status: i32,
+}
+
+/// Why a remote command produced no usable output.
+#[derive(Debug, Clone)]
+pub enum CommandError {
+    /// The SSH channel could not be opened, executed, read or closed.
+    Channel(String),
+    /// The command ran but exited non-zero.
+    Exit { status: i32, stderr: String },
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Channel(msg) => write!(f, "{msg}"),
+            CommandError::Exit { status, stderr } => match stderr.lines().next() {
+                Some(line) if !line.trim().is_empty() => {
+                    write!(f, "Command exited with status {status}: {}", line.trim())
+                }
+                _ => write!(f, "Command exited with status {status}"),
+            },
+        }
+    }
+}
+
+impl From<CommandError> for String {
+    fn from(e: CommandError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Runs `command` under `/bin/sh` with the C locale, whatever the login shell
+/// and locale of the remote user, so that POSIX syntax and `.` decimals hold.
+pub fn exec_command(session: &Session, command: &str) -> Result<CommandOutput, CommandError> {
+    let channel_error = |what: &str, e: ssh2::Error| CommandError::Channel(format!("{what}: {e}"));
     let mut channel = session
         .channel_session()
-        .map_err(|e| format!("Channel error: {}", e))?;
+        .map_err(|e| channel_error("Channel error", e))?;
     channel
-        .exec(command)
-        .map_err(|e| format!("Exec error: {}", e))?;
+        .exec(&format!("env LC_ALL=C /bin/sh -c {}", shell_quote(command)))
+        .map_err(|e| channel_error("Exec error", e))?;
 
-    let mut output = String::new();
-    channel
-        .read_to_string(&mut output)
-        .map_err(|e| format!("Read error: {}", e))?;
+    session.set_blocking(false);
+    let streams = read_both_streams(&mut channel);
+    session.set_blocking(true);
+    let (stdout, stderr) =
+        streams.map_err(|e| CommandError::Channel(format!("Read error: {e}")))?;
     channel
         .wait_close()
-        .map_err(|e| format!("Wait close error: {}", e))?;
+        .map_err(|e| channel_error("Wait close error", e))?;
+    let exit_status = channel
+        .exit_status()
+        .map_err(|e| channel_error("Exit status error", e))?;
+
+    Ok(CommandOutput {
+        stdout: String::from_utf8_lossy(&stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&stderr).into_owned(),
+        exit_status,
+    })
+}
+
+/// Drains stdout and stderr together on a non-blocking session. Reading one
+/// to EOF first would deadlock once the other fills the channel window.
+fn read_both_streams(channel: &mut Channel) -> io::Result<(Vec<u8>, Vec<u8>)> {
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    let (mut stdout_done, mut stderr_done) = (false, false);
+    let mut buf = [0u8; 8192];
+
+    while !(stdout_done && stderr_done) {
+        let mut progressed = false;
+        if !stdout_done {
+            match channel.read(&mut buf) {
+                Ok(0) => stdout_done = true,
+                Ok(n) => {
+                    stdout.extend_from_slice(&buf[..n]);
+                    progressed = true;
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e),
+            }
+        }
+        if !stderr_done {
+            match channel.stderr().read(&mut buf) {
+                Ok(0) => stderr_done = true,
+                Ok(n) => {
+                    stderr.extend_from_slice(&buf[..n]);
+                    progressed = true;
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e),
+            }
+        }
+        if !progressed {
+            thread::sleep(READ_POLL_INTERVAL);
+        }
+    }
+    Ok((stdout, stderr))
+}
+
+/// Runs `command` and returns its stdout, treating a non-zero exit as an error.
+pub fn run_command(session: &Session, command: &str) -> Result<String, CommandError> {
+    let output = exec_command(session, command)?;
+    if output.exit_status != 0 {
+        return Err(CommandError::Exit {
+            status: output.exit_status,
+            stderr: output.stderr,
+        });
+    }
+    Ok(output.stdout)
+}
+
+/// Runs `command` and returns its stdout whatever the exit status, for tools
+/// that exit non-zero with usable partial output (`df`, `grep`, `lsof`, ...).
+pub fn run_command_lenient(session: &Session, command: &str) -> Result<String, CommandError> {
+    exec_command(session, command).map(|output| output.stdout)
+}
+
+/// Quotes `value` for a POSIX shell so configured names cannot inject commands.
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
 
-    Ok(output)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_error_message_and_quoting() {
+        let err = CommandError::Exit {
+            status: 2,
+            stderr: "df: /mnt/stale: Stale file handle\nmore\n".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Command exited with status 2: df: /mnt/stale: Stale file handle"
+        );
+        let err = CommandError::Exit {
+            status: 1,
+            stderr: String::new(),
+        };
+        assert_eq!(String::from(err), "Command exited with status 1");
+
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+
+    #[test]
+    fn test_handshake_slots_are_limited_per_host() {
+        let host = "192.0.2.1:22";
+        let slots: Vec<HandshakeSlot> = (0..MAX_HANDSHAKES_PER_HOST)
+            .map(|_| HandshakeSlot::acquire(host))
+            .collect();
+        // Other hosts are not held up
+        let other = HandshakeSlot::acquire("192.0.2.2:22");
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let waiter = thread::spawn(move || {
+            let _slot = HandshakeSlot::acquire(host);
+            sender.send(()).unwrap();
+        });
+        assert!(receiver.recv_timeout(Duration::from_millis(100)).is_err());
+        drop(slots);
+        assert!(receiver.recv_timeout(Duration::from_secs(5)).is_ok());
+        waiter.join().unwrap();
+        drop(other);
+    }
 }
@@ -0,0 +1,386 @@
+use super::platform::{Platform, detect_platform};
+use super::ssh_hosts::SshHostInfo;
+use super::ssh_utils::{connect_ssh_session, run_command, run_command_lenient};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub const NO_PACKAGE_MANAGER: &str = "No supported package manager found";
+
+/// Non-login SSH sessions often miss sbin and Homebrew directories.
+const PATH_PREFIX: &str =
+    "PATH=\"$PATH:/usr/sbin:/sbin:/opt/homebrew/bin:/usr/local/bin\"; export PATH; ";
+
+#[derive(Debug, Clone)]
+pub struct UpdateStatus {
+    pub manager: String,
+    /// Names of packages with a pending update.
+    pub pending: Vec<String>,
+    /// `None` when the package manager does not classify updates.
+    pub security: Option<u32>,
+    pub running_kernel: Option<String>,
+    /// Newest kernel of the running kernel's flavor under `/lib/modules`,
+    /// or `freebsd-version -k`.
+    pub installed_kernel: Option<String>,
+    pub reboot_reasons: Vec<String>,
+}
+
+impl UpdateStatus {
+    pub fn reboot_required(&self) -> bool {
+        !self.reboot_reasons.is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum UpdateInfo {
+    Loading,
+    Success { status: UpdateStatus },
+    Failure(String),
+}
+
+pub type SharedUpdateInfo = Arc<Mutex<HashMap<String, UpdateInfo>>>;
+
+impl UpdateInfo {
+    pub fn success(status: UpdateStatus) -> Self {
+        UpdateInfo::Success { status }
+    }
+
+    pub fn failure(msg: impl Into<String>) -> Self {
+        UpdateInfo::Failure(msg.into())
+    }
+}
+
+pub fn fetch_update_info(info: &SshHostInfo) -> UpdateInfo {
+    let session = match connect_ssh_session(info) {
+        Ok(s) => s,
+        Err(e) => return UpdateInfo::failure(e),
+    };
+
+    let platform = match detect_platform(&session, info) {
+        Ok(p) => p,
+        Err(e) => return UpdateInfo::failure(e),
+    };
+
+    let detect_cmd = format!(
+        "{PATH_PREFIX}for m in apt-get dnf yum zypper pacman apk opkg pkg syspatch brew; do \
+         command -v $m >/dev/null 2>&1 && {{ echo $m; break; }}; done"
+    );
+    let manager = match run_command_lenient(&session, &detect_cmd) {
+        Ok(out) => out.trim().to_string(),
+        Err(e) => return UpdateInfo::failure(e),
+    };
+
+    // Listings only read cached metadata, so nothing here needs root or the
+    // network. dnf and yum would refresh a per-user copy of the repo metadata
+    // on every run without `-C`, which reads the system cache instead.
+    let (list_cmd, security_cmd) = match manager.as_str() {
+        "apt-get" => (
+            "apt-get -s -o Debug::NoLocking=1 dist-upgrade 2>/dev/null",
+            None,
+        ),
+        "dnf" => (
+            "dnf -q -C check-update 2>/dev/null",
+            Some("dnf -q -C updateinfo list --security 2>/dev/null"),
+        ),
+        "yum" => (
+            "yum -q -C check-update 2>/dev/null",
+            Some("yum -q -C updateinfo list security 2>/dev/null"),
+        ),
+        "zypper" => (
+            "zypper -q --non-interactive list-updates 2>/dev/null",
+            Some("zypper -q --non-interactive list-patches --category security 2>/dev/null"),
+        ),
+        "pacman" => ("checkupdates 2>/dev/null || pacman -Qu 2>/dev/null", None),
+        "apk" => ("apk -u list 2>/dev/null", None),
+        "opkg" => ("opkg list-upgradable 2>/dev/null", None),
+        "pkg" => (
+            "pkg version -vRL= 2>/dev/null",
+            Some("pkg audit -q 2>/dev/null"),
+        ),
+        // OpenBSD base patches are all security or reliability fixes
+        "syspatch" => ("syspatch -c 2>/dev/null", None),
+        "brew" => (
+            "HOMEBREW_NO_AUTO_UPDATE=1 brew outdated --quiet 2>/dev/null",
+            None,
+        ),
+        _ => return UpdateInfo::failure(NO_PACKAGE_MANAGER),
+    };
+
+    // dnf and yum exit 100 when updates are pending, pacman -Qu 1 when none are
+    let output = match run_command_lenient(&session, &format!("{PATH_PREFIX}{list_cmd}")) {
+        Ok(out) => out,
+        Err(e) => return UpdateInfo::failure(e),
+    };
+    let (pending, apt_security) = parse_pending(&manager, &output);
+    let security = match security_cmd {
+        Some(cmd) => run_command_lenient(&session, &format!("{PATH_PREFIX}{cmd}"))
+            .ok()
+            .map(|out| count_security(&manager, &out)),
+        None => apt_security,
+    };
+
+    let mut status = UpdateStatus {
+        manager: manager.trim_end_matches("-get").to_string(),
+        pending,
+        security,
+        running_kernel: None,
+        installed_kernel: None,
+        reboot_reasons: Vec::new(),
+    };
+
+    if platform.is_linux() {
+        // Distros that drop the old modules on upgrade leave nothing for the
+        // running kernel. Every installed kernel is listed so the newest one
+        // of the running flavor can be picked.
+        let reboot_cmd = format!(
+            "{PATH_PREFIX}[ -f /var/run/reboot-required ] && echo flag; \
+             sed 's/^/pkg /' /var/run/reboot-required.pkgs 2>/dev/null; \
+             if command -v needs-restarting >/dev/null 2>&1; then \
+             needs-restarting -r >/dev/null 2>&1; echo \"needs-restarting $?\"; fi; \
+             if command -v zypper >/dev/null 2>&1; then \
+             zypper -q needs-rebooting >/dev/null 2>&1; echo \"zypper $?\"; fi; \
+             [ -d /lib/modules ] && [ ! -d \"/lib/modules/$(uname -r)\" ] && echo modules-missing; \
+             echo \"running $(uname -r)\"; \
+             ls -1 /lib/modules 2>/dev/null | sed 's/^/module /'"
+        );
+        if let Ok(out) = run_command(&session, &reboot_cmd) {
+            apply_reboot_check(&mut status, &out);
+        }
+    } else if platform == Platform::FreeBsd {
+        let reboot_cmd = "echo \"running $(uname -r)\"; \
+                          echo \"installed $(freebsd-version -k 2>/dev/null)\"";
+        if let Ok(out) = run_command(&session, reboot_cmd) {
+            apply_reboot_check(&mut status, &out);
+        }
+    }
+
+    UpdateInfo::success(status)
+}
+
+/// Returns pending package names, plus the security count for apt which
+/// reports the origin archive on every `Inst` line.
+fn parse_pending(manager: &str, output: &str) -> (Vec<String>, Option<u32>) {
+    let lines = output.lines().map(str::trim).filter(|l| !l.is_empty());
+    match manager {
+        // Inst libssl3 [3.0.2-0ubuntu1.10] (3.0.2-0ubuntu1.12 Ubuntu:22.04/jammy-security [amd64])
+        "apt-get" => {
+            let inst: Vec<&str> = lines.filter(|l| l.starts_with("Inst ")).collect();
+            let security = inst
+                .iter()
+                .filter(|l| l.to_lowercase().contains("security"))
+                .count() as u32;
+            let names = inst
+                .iter()
+                .filter_map(|l| l.split_whitespace().nth(1))
+                .map(String::from)
+                .collect();
+            (names, Some(security))
+        }
+        // openssl.x86_64   1:3.0.7-27.el9   baseos; obsoletes follow in their own section
+        "dnf" | "yum" => {
+            let names = lines
+                .take_while(|l| !l.starts_with("Obsoleting"))
+                .filter_map(|l| {
+                    let f: Vec<&str> = l.split_whitespace().collect();
+                    (f.len() == 3 && f[0].contains('.')).then(|| f[0].to_string())
+                })
+                .collect();
+            (names, None)
+        }
+        // v | repo | name | current | available | arch
+        "zypper" => {
+            let names = lines
+                .filter(|l| l.starts_with("v "))
+                .filter_map(|l| l.split('|').nth(2))
+                .map(|n| n.trim().to_string())
+                .collect();
+            (names, None)
+        }
+        "syspatch" => {
+            let names: Vec<String> = lines.map(String::from).collect();
+            let security = names.len() as u32;
+            (names, Some(security))
+        }
+        // pacman: "name old -> new"; brew: one name per line; apk, opkg and
+        // pkg lead with the package name and version
+        _ => {
+            let names = lines
+                .filter_map(|l| l.split_whitespace().next())
+                .map(String::from)
+                .collect();
+            (names, None)
+        }
+    }
+}
+
+fn count_security(manager: &str, output: &str) -> u32 {
+    let lines = output.lines().map(str::trim);
+    match manager {
+        // pkg audit -q prints one vulnerable package per line
+        "pkg" => lines.filter(|l| !l.is_empty()).count() as u32,
+        // | 1234 | openSUSE-SLE-15.5-2024-1234 | security | important | --- | needed | ...
+        "zypper" => lines
+            .filter(|l| l.contains("| security") && l.contains("needed"))
+            .count() as u32,
+        // RHSA-2024:1234 Important/Sec. openssl-1:3.0.7-27.el9.x86_64
+        _ => lines
+            .filter_map(|l| {
+                let f: Vec<&str> = l.split_whitespace().collect();
+                (f.len() == 3 && (f[1].ends_with("Sec.") || f[1] == "security")).then_some(f[2])
+            })
+            .collect::<HashSet<_>>()
+            .len() as u32,
+    }
+}
+
+/// Reads the output of the reboot check script into `status`.
+fn apply_reboot_check(status: &mut UpdateStatus, output: &str) {
+    let mut flag = false;
+    let mut flag_packages = Vec::new();
+    let mut modules = Vec::new();
+
+    for line in output.lines().map(str::trim) {
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        let value = value.trim();
+        match key {
+            "flag" => flag = true,
+            "pkg" => flag_packages.push(value.to_string()),
+            // needs-restarting exits 1 and zypper 102 when a reboot is needed
+            "needs-restarting" if value == "1" => status
+                .reboot_reasons
+                .push("needs-restarting -r".to_string()),
+            "zypper" if value == "102" => status
+                .reboot_reasons
+                .push("zypper needs-rebooting".to_string()),
+            "modules-missing" => status
+                .reboot_reasons
+                .push("modules for the running kernel were removed".to_string()),
+            "running" if !value.is_empty() => status.running_kernel = Some(value.to_string()),
+            "installed" if !value.is_empty() => status.installed_kernel = Some(value.to_string()),
+            "module" if !value.is_empty() => modules.push(value),
+            _ => {}
+        }
+    }
+    if let Some(running) = &status.running_kernel
+        && status.installed_kernel.is_none()
+    {
+        status.installed_kernel = modules
+            .into_iter()
+            .filter(|m| kernel_flavor(m) == kernel_flavor(running))
+            .max_by(|a, b| compare_versions(a, b))
+            .map(String::from);
+    }
+
+    if flag {
+        let reason = if flag_packages.is_empty() {
+            "/var/run/reboot-required".to_string()
+        } else {
+            format!("/var/run/reboot-required ({})", flag_packages.join(", "))
+        };
+        status.reboot_reasons.insert(0, reason);
+    }
+    if let (Some(running), Some(installed)) = (&status.running_kernel, &status.installed_kernel)
+        && running != installed
+    {
+        status
+            .reboot_reasons
+            .push(format!("kernel {running} running, {installed} installed"));
+    }
+}
+
+/// Flavor of a kernel release: the trailing dash-separated words
+/// (`generic`, `cloud-amd64`, `lts`) plus any `+variant`, so that e.g. a
+/// newer `-lowlatency` kernel does not look like an update of `-generic`.
+fn kernel_flavor(release: &str) -> (String, &str) {
+    let (release, variant) = release.split_once('+').unwrap_or((release, ""));
+    let mut words: Vec<&str> = release
+        .rsplit('-')
+        .take_while(|w| !w.starts_with(|c: char| c.is_ascii_digit()))
+        .collect();
+    words.reverse();
+    (words.join("-"), variant)
+}
+
+/// Orders release strings like `sort -V`: runs of digits compare as numbers.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn chunks(s: &str) -> Vec<Result<u64, &str>> {
+        let mut chunks = Vec::new();
+        let mut rest = s;
+        while let Some(first) = rest.chars().next() {
+            let digits = first.is_ascii_digit();
+            let end = rest
+                .find(|c: char| c.is_ascii_digit() != digits)
+                .unwrap_or(rest.len());
+            let (chunk, tail) = rest.split_at(end);
+            chunks.push(if digits {
+                Ok(chunk.parse().unwrap_or(u64::MAX))
+            } else {
+                Err(chunk)
+            });
+            rest = tail;
+        }
+        chunks
+    }
+    chunks(a).cmp(&chunks(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apt_pending_and_reboot_check() {
+        let apt = "\
+NOTE: This is only a simulation!
+Inst libssl3 [3.0.2-0ubuntu1.10] (3.0.2-0ubuntu1.12 Ubuntu:22.04/jammy-security [amd64])
+Inst tzdata [2024a-0ubuntu0.22.04] (2024a-0ubuntu0.22.04.1 Ubuntu:22.04/jammy-updates [all])
+Conf libssl3 (3.0.2-0ubuntu1.12 Ubuntu:22.04/jammy-security [amd64])
+";
+        let (pending, security) = parse_pending("apt-get", apt);
+        assert_eq!(pending, vec!["libssl3", "tzdata"]);
+        assert_eq!(security, Some(1));
+
+        let mut status = UpdateStatus {
+            manager: "apt".to_string(),
+            pending,
+            security,
+            running_kernel: None,
+            installed_kernel: None,
+            reboot_reasons: Vec::new(),
+        };
+        let reboot = "\
+flag
+pkg linux-image-6.5.0-35-generic
+running 6.5.0-28-generic
+module 6.5.0-9-generic
+module 6.5.0-28-generic
+module 6.5.0-35-generic
+module 6.8.0-40-lowlatency
+";
+        apply_reboot_check(&mut status, reboot);
+
+        assert!(status.reboot_required());
+        assert_eq!(
+            status.reboot_reasons,
+            vec![
+                "/var/run/reboot-required (linux-image-6.5.0-35-generic)",
+                "kernel 6.5.0-28-generic running, 6.5.0-35-generic installed",
+            ]
+        );
+
+        // A newer kernel of another flavor is not a pending reboot
+        status.reboot_reasons.clear();
+        status.installed_kernel = None;
+        apply_reboot_check(
+            &mut status,
+            "running 6.5.0-35-generic\nmodule 6.5.0-35-generic\nmodule 6.8.0-40-lowlatency\n",
+        );
+        assert!(!status.reboot_required());
+        assert_eq!(kernel_flavor("6.1.0-18-cloud-amd64").0, "cloud-amd64");
+        assert_eq!(
+            kernel_flavor("6.8.9-300.fc40.x86_64+debug"),
+            ("".into(), "debug")
+        );
+    }
+}
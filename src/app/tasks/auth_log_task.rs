@@ -0,0 +1,62 @@
+use super::task::BackgroundTask;
+use crate::app::states::{
+    AuthLogInfo, SharedAppConfig, SharedAuthLogInfo, SharedSshHosts, fetch_auth_log_info,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::{task, time::timeout};
+
+pub struct AuthLogInfoTask {
+    pub ssh_hosts: SharedSshHosts,
+    pub auth_log_info: SharedAuthLogInfo,
+    pub config: SharedAppConfig,
+}
+
+#[async_trait]
+impl BackgroundTask for AuthLogInfoTask {
+    fn name(&self) -> &'static str {
+        "auth_log_checker"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(5 * 60)
+    }
+
+    async fn run(&self) {
+        let hosts_info = {
+            let hosts = self.ssh_hosts.lock().await;
+            hosts.values().cloned().collect::<Vec<_>>()
+        };
+
+        for info in hosts_info {
+            let auth_log_info = Arc::clone(&self.auth_log_info);
+            let config = Arc::clone(&self.config);
+            let host_id = info.id.clone();
+
+            tokio::spawn(async move {
+                {
+                    let mut statuses = auth_log_info.lock().await;
+                    statuses.insert(host_id.clone(), AuthLogInfo::Loading);
+                }
+
+                let result = timeout(
+                    Duration::from_secs(30),
+                    task::spawn_blocking(move || fetch_auth_log_info(&info, &config.auth_log)),
+                )
+                .await;
+
+                let auth_result = match result {
+                    Ok(Ok(info)) => info,
+                    Ok(Err(e)) => AuthLogInfo::failure(format!("Thread error: {e}")),
+                    Err(_) => AuthLogInfo::failure("Timed out"),
+                };
+
+                {
+                    let mut statuses = auth_log_info.lock().await;
+                    statuses.insert(host_id, auth_result);
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use super::task::BackgroundTask;
+use crate::app::states::{
+    ClockInfo, SharedAppConfig, SharedClockInfo, SharedSshHosts, fetch_clock_info,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::{task, time::timeout};
+
+pub struct ClockInfoTask {
+    pub ssh_hosts: SharedSshHosts,
+    pub clock_info: SharedClockInfo,
+    pub config: SharedAppConfig,
+}
+
+#[async_trait]
+impl BackgroundTask for ClockInfoTask {
+    fn name(&self) -> &'static str {
+        "clock_info_checker"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+
+    async fn run(&self) {
+        let hosts_info = {
+            let hosts = self.ssh_hosts.lock().await;
+            hosts.values().cloned().collect::<Vec<_>>()
+        };
+
+        for info in hosts_info {
+            let clock_info = Arc::clone(&self.clock_info);
+            let config = Arc::clone(&self.config);
+            let host_id = info.id.clone();
+
+            tokio::spawn(async move {
+                {
+                    let mut statuses = clock_info.lock().await;
+                    statuses.insert(host_id.clone(), ClockInfo::Loading);
+                }
+
+                let result = timeout(
+                    Duration::from_secs(10),
+                    task::spawn_blocking(move || fetch_clock_info(&info, &config.clock)),
+                )
+                .await;
+
+                let clock_result = match result {
+                    Ok(Ok(info)) => info,
+                    Ok(Err(e)) => ClockInfo::failure(format!("Thread error: {e}")),
+                    Err(_) => ClockInfo::failure("Timed out"),
+                };
+
+                {
+                    let mut statuses = clock_info.lock().await;
+                    statuses.insert(host_id, clock_result);
+                }
+            });
+        }
+    }
+}
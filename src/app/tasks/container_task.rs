@@ -0,0 +1,60 @@
+use super::task::BackgroundTask;
+use crate::app::states::{
+    ContainerInfo, SharedContainerInfo, SharedSshHosts, fetch_container_info,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::{task, time::timeout};
+
+pub struct ContainerInfoTask {
+    pub ssh_hosts: SharedSshHosts,
+    pub container_info: SharedContainerInfo,
+}
+
+#[async_trait]
+impl BackgroundTask for ContainerInfoTask {
+    fn name(&self) -> &'static str {
+        "container_info_checker"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(30)
+    }
+
+    async fn run(&self) {
+        let hosts_info = {
+            let hosts = self.ssh_hosts.lock().await;
+            hosts.values().cloned().collect::<Vec<_>>()
+        };
+
+        for info in hosts_info {
+            let container_info = Arc::clone(&self.container_info);
+            let host_id = info.id.clone();
+
+            tokio::spawn(async move {
+                {
+                    let mut statuses = container_info.lock().await;
+                    statuses.insert(host_id.clone(), ContainerInfo::Loading);
+                }
+
+                let result = timeout(
+                    Duration::from_secs(10),
+                    task::spawn_blocking(move || fetch_container_info(&info)),
+                )
+                .await;
+
+                let container_result = match result {
+                    Ok(Ok(info)) => info,
+                    Ok(Err(e)) => ContainerInfo::failure(format!("Thread error: {e}")),
+                    Err(_) => ContainerInfo::failure("Timed out"),
+                };
+
+                {
+                    let mut statuses = container_info.lock().await;
+                    statuses.insert(host_id, container_result);
+                }
+            });
+        }
+    }
+}
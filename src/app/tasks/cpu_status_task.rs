@@ -1,5 +1,8 @@
 use super::task::BackgroundTask;
-use crate::app::states::{CpuInfo, SharedCpuInfo, SharedSshHosts, fetch_cpu_info};
+use crate::app::states::{
+    CpuInfo, Metric, SharedCpuInfo, SharedHistory, SharedHistoryStore, SharedSshHosts,
+    fetch_cpu_info,
+};
 use async_trait::async_trait;
 use std::sync::Arc;
 use std::time::Duration;
@@ -8,6 +11,8 @@ use tokio::{task, time::timeout};
 pub struct CpuInfoTask {
     pub ssh_hosts: SharedSshHosts,
     pub cpu_info: SharedCpuInfo,
+    pub history: SharedHistory,
+    pub history_store: SharedHistoryStore,
 }
 
 #[async_trait]
@@ -28,6 +33,8 @@ impl BackgroundTask for CpuInfoTask {
 
         for info in hosts_info {
             let cpu_info = Arc::clone(&self.cpu_info);
+            let history = Arc::clone(&self.history);
+            let history_store = Arc::clone(&self.history_store);
             let host_id = info.id.clone();
 
             tokio::spawn(async move {
@@ -50,6 +57,17 @@ impl BackgroundTask for CpuInfoTask {
                     Err(_) => CpuInfo::failure("Timed out"),
                 };
 
+                // Usage sums over cores; the chart plots it against 100%
+                if let CpuInfo::Success {
+                    core_count,
+                    usage_percent,
+                } = &cpu_result
+                {
+                    let usage = *usage_percent as f64 / (*core_count).max(1) as f64;
+                    let mut history = history.lock().await;
+                    history_store.record(&mut history, &host_id, Metric::Cpu, usage.min(100.0));
+                }
+
                 // Update map
                 {
                     let mut statuses = cpu_info.lock().await;
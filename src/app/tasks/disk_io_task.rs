@@ -0,0 +1,58 @@
+use super::task::BackgroundTask;
+use crate::app::states::{DiskIoInfo, SharedDiskIoInfo, SharedSshHosts, fetch_disk_io_info};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::{task, time::timeout};
+
+pub struct DiskIoInfoTask {
+    pub ssh_hosts: SharedSshHosts,
+    pub disk_io_info: SharedDiskIoInfo,
+}
+
+#[async_trait]
+impl BackgroundTask for DiskIoInfoTask {
+    fn name(&self) -> &'static str {
+        "disk_io_info_checker"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(30)
+    }
+
+    async fn run(&self) {
+        let hosts_info = {
+            let hosts = self.ssh_hosts.lock().await;
+            hosts.values().cloned().collect::<Vec<_>>()
+        };
+
+        for info in hosts_info {
+            let disk_io_info = Arc::clone(&self.disk_io_info);
+            let host_id = info.id.clone();
+
+            tokio::spawn(async move {
+                {
+                    let mut statuses = disk_io_info.lock().await;
+                    statuses.insert(host_id.clone(), DiskIoInfo::Loading);
+                }
+
+                let result = timeout(
+                    Duration::from_secs(10),
+                    task::spawn_blocking(move || fetch_disk_io_info(&info)),
+                )
+                .await;
+
+                let io_result = match result {
+                    Ok(Ok(info)) => info,
+                    Ok(Err(e)) => DiskIoInfo::failure(format!("Thread error: {e}")),
+                    Err(_) => DiskIoInfo::failure("Timed out"),
+                };
+
+                {
+                    let mut statuses = disk_io_info.lock().await;
+                    statuses.insert(host_id, io_result);
+                }
+            });
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use super::task::BackgroundTask;
-use crate::app::states::{DiskInfo, SharedDiskInfo, SharedSshHosts, fetch_disk_info};
+use crate::app::states::{
+    DiskInfo, SharedAppConfig, SharedDiskInfo, SharedSshHosts, fetch_disk_info,
+};
 use async_trait::async_trait;
 use std::sync::Arc;
 use std::time::Duration;
@@ -8,6 +10,7 @@ use tokio::{task, time::timeout};
 pub struct DiskInfoTask {
     pub ssh_hosts: SharedSshHosts,
     pub disk_info: SharedDiskInfo,
+    pub config: SharedAppConfig,
 }
 
 #[async_trait]
@@ -28,6 +31,7 @@ impl BackgroundTask for DiskInfoTask {
 
         for info in hosts_info {
             let disk_info = Arc::clone(&self.disk_info);
+            let config = Arc::clone(&self.config);
             let host_id = info.id.clone();
 
             tokio::spawn(async move {
@@ -38,7 +42,7 @@ impl BackgroundTask for DiskInfoTask {
 
                 let result = timeout(
                     Duration::from_secs(10),
-                    task::spawn_blocking(move || fetch_disk_info(&info)),
+                    task::spawn_blocking(move || fetch_disk_info(&info, &config.disk)),
                 )
                 .await;
 
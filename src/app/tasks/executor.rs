@@ -1,5 +1,9 @@
 use super::task::BackgroundTask;
-use tokio::time::sleep;
+use tokio::time::{Duration, sleep};
+
+/// Delay between the first runs of consecutive tasks, so they do not all
+/// connect to every host at the same instant.
+const FIRST_RUN_STAGGER: Duration = Duration::from_millis(200);
 
 pub struct TaskExecutor {
     tasks: Vec<Box<dyn BackgroundTask>>,
@@ -15,10 +19,12 @@ impl TaskExecutor {
     }
 
     pub fn start(self) {
-        for task in self.tasks {
+        for (i, task) in self.tasks.into_iter().enumerate() {
             let interval = task.interval();
             let name = task.name();
+            let first_run = FIRST_RUN_STAGGER * i as u32;
             tokio::spawn(async move {
+                sleep(first_run).await;
                 loop {
                     tracing::debug!("Running task: {}", name);
                     task.run().await;
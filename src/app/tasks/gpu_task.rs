@@ -1,13 +1,18 @@
 use super::task::BackgroundTask;
-use crate::app::states::{GpuInfo, SharedGpuInfo, SharedSshHosts, fetch_gpu_info};
+use crate::app::states::{
+    GpuInfo, Metric, SharedGpuInfo, SharedHistory, SharedHistoryStore, SharedSshHosts,
+    fetch_gpu_info,
+};
 use async_trait::async_trait;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::{task, time::timeout};
 
 pub struct GpuInfoTask {
     pub ssh_hosts: SharedSshHosts,
     pub gpu_info: SharedGpuInfo,
+    pub history: SharedHistory,
+    pub history_store: SharedHistoryStore,
 }
 
 #[async_trait]
@@ -28,13 +33,22 @@ impl BackgroundTask for GpuInfoTask {
 
         for info in hosts_info {
             let gpu_info = Arc::clone(&self.gpu_info);
+            let history = Arc::clone(&self.history);
+            let history_store = Arc::clone(&self.history_store);
             let host_id = info.id.clone();
 
             tokio::spawn(async move {
-                {
+                // Keep showing the last devices while refreshing so the fleet
+                // view does not drop them and move the selection; the previous
+                // sample also carries idle streaks over.
+                let previous = {
                     let mut statuses = gpu_info.lock().await;
-                    statuses.insert(host_id.clone(), GpuInfo::Loading);
-                }
+                    let previous = statuses.get(&host_id).cloned();
+                    if !matches!(previous, Some(GpuInfo::Success { .. })) {
+                        statuses.insert(host_id.clone(), GpuInfo::Loading);
+                    }
+                    previous
+                };
 
                 let result = timeout(
                     Duration::from_secs(10),
@@ -42,11 +56,26 @@ impl BackgroundTask for GpuInfoTask {
                 )
                 .await;
 
-                let gpu_result = match result {
+                let mut gpu_result = match result {
                     Ok(Ok(info)) => info,
                     Ok(Err(e)) => GpuInfo::failure(format!("Thread error: {e}")),
                     Err(_) => GpuInfo::failure("Timed out"),
                 };
+                gpu_result.track_idle(previous.as_ref(), Instant::now());
+
+                // One series per host, averaged across its GPUs
+                if let GpuInfo::Success { devices } = &gpu_result
+                    && !devices.is_empty()
+                {
+                    let total: f64 = devices.iter().map(|d| d.utilization_percent as f64).sum();
+                    let mut history = history.lock().await;
+                    history_store.record(
+                        &mut history,
+                        &host_id,
+                        Metric::Gpu,
+                        total / devices.len() as f64,
+                    );
+                }
 
                 {
                     let mut statuses = gpu_info.lock().await;
@@ -0,0 +1,31 @@
+use super::task::BackgroundTask;
+use crate::app::states::SharedHistoryStore;
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task;
+
+/// Rewrites the history file with retention and downsampling applied.
+pub struct HistoryCompactTask {
+    pub history_store: SharedHistoryStore,
+}
+
+#[async_trait]
+impl BackgroundTask for HistoryCompactTask {
+    fn name(&self) -> &'static str {
+        "history_compactor"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(60 * 60)
+    }
+
+    async fn run(&self) {
+        let store = Arc::clone(&self.history_store);
+        match task::spawn_blocking(move || store.compact()).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => tracing::warn!("Failed to compact history: {e}"),
+            Err(e) => tracing::warn!("History compaction thread error: {e}"),
+        }
+    }
+}
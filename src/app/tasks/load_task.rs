@@ -0,0 +1,58 @@
+use super::task::BackgroundTask;
+use crate::app::states::{LoadInfo, SharedLoadInfo, SharedSshHosts, fetch_load_info};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::{task, time::timeout};
+
+pub struct LoadInfoTask {
+    pub ssh_hosts: SharedSshHosts,
+    pub load_info: SharedLoadInfo,
+}
+
+#[async_trait]
+impl BackgroundTask for LoadInfoTask {
+    fn name(&self) -> &'static str {
+        "load_info_checker"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(30)
+    }
+
+    async fn run(&self) {
+        let hosts_info = {
+            let hosts = self.ssh_hosts.lock().await;
+            hosts.values().cloned().collect::<Vec<_>>()
+        };
+
+        for info in hosts_info {
+            let load_info = Arc::clone(&self.load_info);
+            let host_id = info.id.clone();
+
+            tokio::spawn(async move {
+                {
+                    let mut statuses = load_info.lock().await;
+                    statuses.insert(host_id.clone(), LoadInfo::Loading);
+                }
+
+                let result = timeout(
+                    Duration::from_secs(10),
+                    task::spawn_blocking(move || fetch_load_info(&info)),
+                )
+                .await;
+
+                let load_result = match result {
+                    Ok(Ok(info)) => info,
+                    Ok(Err(e)) => LoadInfo::failure(format!("Thread error: {e}")),
+                    Err(_) => LoadInfo::failure("Timed out"),
+                };
+
+                {
+                    let mut statuses = load_info.lock().await;
+                    statuses.insert(host_id, load_result);
+                }
+            });
+        }
+    }
+}
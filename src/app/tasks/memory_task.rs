@@ -1,5 +1,8 @@
 use super::task::BackgroundTask;
-use crate::app::states::{MemoryInfo, SharedMemoryInfo, SharedSshHosts, fetch_memory_info};
+use crate::app::states::{
+    MemoryInfo, Metric, SharedHistory, SharedHistoryStore, SharedMemoryInfo, SharedSshHosts,
+    fetch_memory_info,
+};
 use async_trait::async_trait;
 use std::sync::Arc;
 use std::time::Duration;
@@ -8,6 +11,8 @@ use tokio::{task, time::timeout};
 pub struct MemoryInfoTask {
     pub ssh_hosts: SharedSshHosts,
     pub memory_info: SharedMemoryInfo,
+    pub history: SharedHistory,
+    pub history_store: SharedHistoryStore,
 }
 
 #[async_trait]
@@ -28,6 +33,8 @@ impl BackgroundTask for MemoryInfoTask {
 
         for info in hosts_info {
             let memory_info = Arc::clone(&self.memory_info);
+            let history = Arc::clone(&self.history);
+            let history_store = Arc::clone(&self.history_store);
             let host_id = info.id.clone();
 
             tokio::spawn(async move {
@@ -48,6 +55,16 @@ impl BackgroundTask for MemoryInfoTask {
                     Err(_) => MemoryInfo::failure("Timed out"),
                 };
 
+                if let MemoryInfo::Success { stats } = &mem_result {
+                    let mut history = history.lock().await;
+                    history_store.record(
+                        &mut history,
+                        &host_id,
+                        Metric::Memory,
+                        stats.usage_percent() as f64,
+                    );
+                }
+
                 {
                     let mut statuses = memory_info.lock().await;
                     statuses.insert(host_id, mem_result);
@@ -1,9 +1,22 @@
 pub mod executor;
 pub mod task;
 
+pub mod auth_log_task;
+pub mod clock_task;
+pub mod container_task;
 pub mod cpu_status_task;
+pub mod disk_io_task;
 pub mod disk_task;
 pub mod gpu_task;
+pub mod history_task;
+pub mod load_task;
 pub mod memory_task;
+pub mod network_task;
 pub mod os_task;
+pub mod port_task;
+pub mod process_task;
+pub mod sensor_task;
+pub mod service_task;
+pub mod session_task;
 pub mod ssh_status_task;
+pub mod update_task;
@@ -0,0 +1,58 @@
+use super::task::BackgroundTask;
+use crate::app::states::{NetworkInfo, SharedNetworkInfo, SharedSshHosts, fetch_network_info};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::{task, time::timeout};
+
+pub struct NetworkInfoTask {
+    pub ssh_hosts: SharedSshHosts,
+    pub network_info: SharedNetworkInfo,
+}
+
+#[async_trait]
+impl BackgroundTask for NetworkInfoTask {
+    fn name(&self) -> &'static str {
+        "network_info_checker"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(30)
+    }
+
+    async fn run(&self) {
+        let hosts_info = {
+            let hosts = self.ssh_hosts.lock().await;
+            hosts.values().cloned().collect::<Vec<_>>()
+        };
+
+        for info in hosts_info {
+            let network_info = Arc::clone(&self.network_info);
+            let host_id = info.id.clone();
+
+            tokio::spawn(async move {
+                {
+                    let mut statuses = network_info.lock().await;
+                    statuses.insert(host_id.clone(), NetworkInfo::Loading);
+                }
+
+                let result = timeout(
+                    Duration::from_secs(10),
+                    task::spawn_blocking(move || fetch_network_info(&info)),
+                )
+                .await;
+
+                let net_result = match result {
+                    Ok(Ok(info)) => info,
+                    Ok(Err(e)) => NetworkInfo::failure(format!("Thread error: {e}")),
+                    Err(_) => NetworkInfo::failure("Timed out"),
+                };
+
+                {
+                    let mut statuses = network_info.lock().await;
+                    statuses.insert(host_id, net_result);
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use super::task::BackgroundTask;
+use crate::app::states::{
+    PortInfo, SharedAppConfig, SharedPortInfo, SharedSshHosts, fetch_port_info,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::{task, time::timeout};
+
+pub struct PortInfoTask {
+    pub ssh_hosts: SharedSshHosts,
+    pub port_info: SharedPortInfo,
+    pub config: SharedAppConfig,
+}
+
+#[async_trait]
+impl BackgroundTask for PortInfoTask {
+    fn name(&self) -> &'static str {
+        "port_info_checker"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+
+    async fn run(&self) {
+        let hosts_info = {
+            let hosts = self.ssh_hosts.lock().await;
+            hosts.values().cloned().collect::<Vec<_>>()
+        };
+
+        for info in hosts_info {
+            let port_info = Arc::clone(&self.port_info);
+            let config = Arc::clone(&self.config);
+            let host_id = info.id.clone();
+
+            tokio::spawn(async move {
+                {
+                    let mut statuses = port_info.lock().await;
+                    statuses.insert(host_id.clone(), PortInfo::Loading);
+                }
+
+                let result = timeout(
+                    Duration::from_secs(10),
+                    task::spawn_blocking(move || {
+                        fetch_port_info(&info, config.allowed_ports(&info.name))
+                    }),
+                )
+                .await;
+
+                let port_result = match result {
+                    Ok(Ok(info)) => info,
+                    Ok(Err(e)) => PortInfo::failure(format!("Thread error: {e}")),
+                    Err(_) => PortInfo::failure("Timed out"),
+                };
+
+                {
+                    let mut statuses = port_info.lock().await;
+                    statuses.insert(host_id, port_result);
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use super::task::BackgroundTask;
+use crate::app::states::{
+    ProcessInfo, SharedFocusedHost, SharedProcessInfo, SharedSshHosts, SshHostInfo,
+    fetch_process_info,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio::{task, time::timeout};
+
+/// Every host is refreshed this often; the focused host on every tick.
+const FULL_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+pub struct ProcessInfoTask {
+    pub ssh_hosts: SharedSshHosts,
+    pub process_info: SharedProcessInfo,
+    pub focused_host: SharedFocusedHost,
+    pub last_full_refresh: Mutex<Option<Instant>>,
+}
+
+#[async_trait]
+impl BackgroundTask for ProcessInfoTask {
+    fn name(&self) -> &'static str {
+        "process_info_checker"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(5)
+    }
+
+    async fn run(&self) {
+        let full_refresh = {
+            let mut last = self.last_full_refresh.lock().await;
+            let due = last.is_none_or(|t| t.elapsed() >= FULL_REFRESH_INTERVAL);
+            if due {
+                *last = Some(Instant::now());
+            }
+            due
+        };
+        let focused = self.focused_host.lock().await.clone();
+
+        let hosts_info = {
+            let hosts = self.ssh_hosts.lock().await;
+            hosts
+                .values()
+                .filter(|h| full_refresh || focused.as_ref() == Some(&h.id))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        for info in hosts_info {
+            let process_info = Arc::clone(&self.process_info);
+            tokio::spawn(refresh_host(info, process_info));
+        }
+    }
+}
+
+async fn refresh_host(info: SshHostInfo, process_info: SharedProcessInfo) {
+    let host_id = info.id.clone();
+
+    // Only show Loading the first time; later refreshes keep the old table
+    // on screen so scrolling and selection are not disturbed.
+    {
+        let mut statuses = process_info.lock().await;
+        statuses
+            .entry(host_id.clone())
+            .or_insert(ProcessInfo::Loading);
+    }
+
+    let result = timeout(
+        Duration::from_secs(10),
+        task::spawn_blocking(move || fetch_process_info(&info)),
+    )
+    .await;
+
+    let process_result = match result {
+        Ok(Ok(info)) => info,
+        Ok(Err(e)) => ProcessInfo::failure(format!("Thread error: {e}")),
+        Err(_) => ProcessInfo::failure("Timed out"),
+    };
+
+    {
+        let mut statuses = process_info.lock().await;
+        statuses.insert(host_id, process_result);
+    }
+}
@@ -0,0 +1,58 @@
+use super::task::BackgroundTask;
+use crate::app::states::{SensorInfo, SharedSensorInfo, SharedSshHosts, fetch_sensor_info};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::{task, time::timeout};
+
+pub struct SensorInfoTask {
+    pub ssh_hosts: SharedSshHosts,
+    pub sensor_info: SharedSensorInfo,
+}
+
+#[async_trait]
+impl BackgroundTask for SensorInfoTask {
+    fn name(&self) -> &'static str {
+        "sensor_info_checker"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(30)
+    }
+
+    async fn run(&self) {
+        let hosts_info = {
+            let hosts = self.ssh_hosts.lock().await;
+            hosts.values().cloned().collect::<Vec<_>>()
+        };
+
+        for info in hosts_info {
+            let sensor_info = Arc::clone(&self.sensor_info);
+            let host_id = info.id.clone();
+
+            tokio::spawn(async move {
+                {
+                    let mut statuses = sensor_info.lock().await;
+                    statuses.insert(host_id.clone(), SensorInfo::Loading);
+                }
+
+                let result = timeout(
+                    Duration::from_secs(10),
+                    task::spawn_blocking(move || fetch_sensor_info(&info)),
+                )
+                .await;
+
+                let sensor_result = match result {
+                    Ok(Ok(info)) => info,
+                    Ok(Err(e)) => SensorInfo::failure(format!("Thread error: {e}")),
+                    Err(_) => SensorInfo::failure("Timed out"),
+                };
+
+                {
+                    let mut statuses = sensor_info.lock().await;
+                    statuses.insert(host_id, sensor_result);
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use super::task::BackgroundTask;
+use crate::app::states::{
+    ServiceInfo, SharedAppConfig, SharedServiceInfo, SharedSshHosts, fetch_service_info,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::{task, time::timeout};
+
+pub struct ServiceInfoTask {
+    pub ssh_hosts: SharedSshHosts,
+    pub service_info: SharedServiceInfo,
+    pub config: SharedAppConfig,
+}
+
+#[async_trait]
+impl BackgroundTask for ServiceInfoTask {
+    fn name(&self) -> &'static str {
+        "service_info_checker"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+
+    async fn run(&self) {
+        let hosts_info = {
+            let hosts = self.ssh_hosts.lock().await;
+            hosts.values().cloned().collect::<Vec<_>>()
+        };
+
+        for info in hosts_info {
+            let service_info = Arc::clone(&self.service_info);
+            let config = Arc::clone(&self.config);
+            let host_id = info.id.clone();
+
+            tokio::spawn(async move {
+                {
+                    let mut statuses = service_info.lock().await;
+                    statuses.insert(host_id.clone(), ServiceInfo::Loading);
+                }
+
+                let result = timeout(
+                    Duration::from_secs(10),
+                    task::spawn_blocking(move || {
+                        fetch_service_info(&info, config.watched_units(&info.name))
+                    }),
+                )
+                .await;
+
+                let service_result = match result {
+                    Ok(Ok(info)) => info,
+                    Ok(Err(e)) => ServiceInfo::failure(format!("Thread error: {e}")),
+                    Err(_) => ServiceInfo::failure("Timed out"),
+                };
+
+                {
+                    let mut statuses = service_info.lock().await;
+                    statuses.insert(host_id, service_result);
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use super::task::BackgroundTask;
+use crate::app::states::{
+    SessionInfo, SharedAppConfig, SharedSessionInfo, SharedSshHosts, fetch_session_info,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::{task, time::timeout};
+
+pub struct SessionInfoTask {
+    pub ssh_hosts: SharedSshHosts,
+    pub session_info: SharedSessionInfo,
+    pub config: SharedAppConfig,
+}
+
+#[async_trait]
+impl BackgroundTask for SessionInfoTask {
+    fn name(&self) -> &'static str {
+        "session_info_checker"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+
+    async fn run(&self) {
+        let hosts_info = {
+            let hosts = self.ssh_hosts.lock().await;
+            hosts.values().cloned().collect::<Vec<_>>()
+        };
+
+        for info in hosts_info {
+            let session_info = Arc::clone(&self.session_info);
+            let config = Arc::clone(&self.config);
+            let host_id = info.id.clone();
+
+            tokio::spawn(async move {
+                {
+                    let mut statuses = session_info.lock().await;
+                    statuses.insert(host_id.clone(), SessionInfo::Loading);
+                }
+
+                let result = timeout(
+                    Duration::from_secs(10),
+                    task::spawn_blocking(move || fetch_session_info(&info, &config.sessions)),
+                )
+                .await;
+
+                let session_result = match result {
+                    Ok(Ok(info)) => info,
+                    Ok(Err(e)) => SessionInfo::failure(format!("Thread error: {e}")),
+                    Err(_) => SessionInfo::failure("Timed out"),
+                };
+
+                {
+                    let mut statuses = session_info.lock().await;
+                    statuses.insert(host_id, session_result);
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use super::task::BackgroundTask;
+use crate::app::states::{SharedSshHosts, SharedUpdateInfo, UpdateInfo, fetch_update_info};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::{task, time::timeout};
+
+pub struct UpdateInfoTask {
+    pub ssh_hosts: SharedSshHosts,
+    pub update_info: SharedUpdateInfo,
+}
+
+#[async_trait]
+impl BackgroundTask for UpdateInfoTask {
+    fn name(&self) -> &'static str {
+        "update_info_checker"
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(60 * 60)
+    }
+
+    async fn run(&self) {
+        let hosts_info = {
+            let hosts = self.ssh_hosts.lock().await;
+            hosts.values().cloned().collect::<Vec<_>>()
+        };
+
+        for info in hosts_info {
+            let update_info = Arc::clone(&self.update_info);
+            let host_id = info.id.clone();
+
+            tokio::spawn(async move {
+                {
+                    let mut statuses = update_info.lock().await;
+                    statuses.insert(host_id.clone(), UpdateInfo::Loading);
+                }
+
+                let result = timeout(
+                    Duration::from_secs(120),
+                    task::spawn_blocking(move || fetch_update_info(&info)),
+                )
+                .await;
+
+                let update_result = match result {
+                    Ok(Ok(info)) => info,
+                    Ok(Err(e)) => UpdateInfo::failure(format!("Thread error: {e}")),
+                    Err(_) => UpdateInfo::failure("Timed out"),
+                };
+
+                {
+                    let mut statuses = update_info.lock().await;
+                    statuses.insert(host_id, update_result);
+                }
+            });
+        }
+    }
+}
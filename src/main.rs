@@ -1,13 +1,28 @@
 use app::App;
 use color_eyre::Result;
+use tracing_subscriber::EnvFilter;
 
 mod app;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     color_eyre::install()?;
+
+    // Collect without a terminal, e.g. on an always-on box; logs are JSON lines
+    if std::env::args().skip(1).any(|arg| arg == "--headless") {
+        tracing_subscriber::fmt()
+            .json()
+            .with_env_filter(
+                EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+            )
+            .init();
+        return App::new()?.run_headless().await;
+    }
+
+    // Load before taking over the terminal so config errors stay readable
+    let app = App::new()?;
     let terminal = ratatui::init();
-    let result = App::new().run(terminal).await;
+    let result = app.run(terminal).await;
     ratatui::restore();
     result
 }